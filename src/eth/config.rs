@@ -15,6 +15,8 @@ pub struct Config {
     #[serde(default, with = "::serde_with::rust::double_option")]
     pub textui: Option<Option<TextUi>>,
     #[serde(default, with = "::serde_with::rust::double_option")]
+    pub listener: Option<Option<Listener>>,
+    #[serde(default, with = "::serde_with::rust::double_option")]
     pub mu: Option<Option<String>>,
 }
 
@@ -36,6 +38,20 @@ pub struct TextUi {
     rows: Option<Option<usize>>,
     #[serde(default, with = "::serde_with::rust::double_option")]
     cursor: Option<Option<usize>>,
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    scrollback: Option<Option<usize>>,
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    timestamps: Option<Option<bool>>,
+}
+
+#[derive(Default, Serialize, Deserialize, Clone)]
+pub struct Listener {
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    history_file: Option<Option<String>>,
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    history_size: Option<Option<usize>>,
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    prompt: Option<Option<String>>,
 }
 
 impl Window {
@@ -100,12 +116,66 @@ impl TextUi {
             },
         }
     }
+
+    pub fn scrollback(config: &Config) -> usize {
+        match &config.textui {
+            Some(None) | None => 1000,
+            Some(Some(window)) => match window.scrollback {
+                Some(None) | None => 1000,
+                Some(scrollback) => scrollback.unwrap(),
+            },
+        }
+    }
+
+    pub fn timestamps(config: &Config) -> bool {
+        match &config.textui {
+            Some(None) | None => false,
+            Some(Some(window)) => match window.timestamps {
+                Some(None) | None => false,
+                Some(timestamps) => timestamps.unwrap(),
+            },
+        }
+    }
+}
+
+impl Listener {
+    pub fn history_file(config: &Config) -> String {
+        match &config.listener {
+            Some(None) | None => "history.json".to_string(),
+            Some(Some(listener)) => match &listener.history_file {
+                Some(None) | None => "history.json".to_string(),
+                Some(Some(file)) => file.to_string(),
+            },
+        }
+    }
+
+    pub fn history_size(config: &Config) -> usize {
+        match &config.listener {
+            Some(None) | None => 500,
+            Some(Some(listener)) => match listener.history_size {
+                Some(None) | None => 500,
+                Some(size) => size.unwrap(),
+            },
+        }
+    }
+
+    // {ns} is replaced by the current namespace
+    pub fn prompt(config: &Config) -> String {
+        match &config.listener {
+            Some(None) | None => "{ns}> ".to_string(),
+            Some(Some(listener)) => match &listener.prompt {
+                Some(None) | None => "{ns}> ".to_string(),
+                Some(Some(prompt)) => prompt.to_string(),
+            },
+        }
+    }
 }
 
 impl Config {
     const DEFAULT: Config = Config {
         window: Option::None,
         textui: Option::None,
+        listener: Option::None,
         mu: Option::None,
     };
 
@@ -94,4 +94,129 @@ impl Core {
             self.system.mu().get_string(self.eval_stream).unwrap(),
         )
     }
+
+    // read, compile and eval expr, errors are returned rather than unwrapped
+    pub fn try_eval(&self, expr: String) -> std::result::Result<Tag, Exception> {
+        let mu = self.system.mu();
+
+        mu.eval(mu.compile(mu.read_string(expr)?)?)
+    }
+
+    // the keyword mu raises a condition as
+    pub fn condition_keyword(condition: Condition) -> &'static str {
+        match condition {
+            Condition::Arity => ":arity",
+            Condition::Eof => ":eof",
+            Condition::Error => ":error",
+            Condition::Except => ":except",
+            Condition::Open => ":open",
+            Condition::Over => ":over",
+            Condition::Range => ":range",
+            Condition::Read => ":read",
+            Condition::Stream => ":stream",
+            Condition::Syntax => ":syntax",
+            Condition::Syscall => ":syscall",
+            Condition::Type => ":type",
+            Condition::Unbound => ":unbound",
+            Condition::Under => ":under",
+            Condition::Write => ":write",
+            Condition::ZeroDivide => ":div0",
+        }
+    }
+
+    // evaluate expr and print mu's repr vector for its value, a Tag can't
+    // be handed back to mu, so objects travel as reprs
+    pub fn repr(&self, expr: &str) -> std::result::Result<String, Exception> {
+        let repr = self.try_eval(format!("(mu:repr :vector {})", expr))?;
+
+        Ok(self.system.write(repr, false))
+    }
+
+    // read the first form in text with ns current, None reads in the current
+    // namespace. the namespace is switched back here rather than in lisp so
+    // a reader error can't leave the core in ns
+    pub fn read_repr(
+        &self,
+        ns: Option<&str>,
+        text: &str,
+    ) -> std::result::Result<String, Exception> {
+        let reader = match self.init_loaded {
+            true => "prelude:read",
+            false => "mu:read",
+        };
+        let read = format!(
+            "({} (mu:open :string :input \"{}\") :t ())",
+            reader,
+            text.replace('\\', "\\\\").replace('"', "\\\"")
+        );
+
+        match ns {
+            None => self.repr(&read),
+            Some(ns) => {
+                let outer = self.repr("(prelude:current-ns)")?;
+
+                self.try_eval(format!("(prelude:in-ns :{})", ns))?;
+                let form = self.repr(&read);
+                self.try_eval(format!("(prelude:in-ns (mu:repr :t {}))", outer))?;
+
+                form
+            }
+        }
+    }
+
+    // the object a repr stands for
+    pub fn from_repr(&self, repr: &str) -> std::result::Result<Tag, Exception> {
+        self.try_eval(format!("(mu:repr :t {})", repr))
+    }
+
+    // bind ns:name to the object for repr, so it needn't be readable
+    pub fn intern_repr(
+        &self,
+        ns: &str,
+        name: &str,
+        repr: &str,
+    ) -> std::result::Result<Tag, Exception> {
+        self.try_eval(format!(
+            "(mu:intern {} \"{}\" (mu:repr :t {}))",
+            ns, name, repr
+        ))
+    }
+
+    // a mu list of symbols as a vector of names
+    pub fn list_to_vec(&self, list: Tag) -> Vec<String> {
+        if self.null(list) {
+            return vec![];
+        }
+
+        let list_str = self.system.write(list, false);
+        let mut chars = list_str.chars();
+
+        chars.next();
+        chars.next_back();
+        chars
+            .as_str()
+            .split(' ')
+            .map(|str| str.to_string())
+            .collect::<Vec<_>>()
+    }
+
+    pub fn list_to_sorted_vec(&self, list: Tag) -> Vec<String> {
+        let mut sorted_vec = self.list_to_vec(list);
+
+        sorted_vec.sort_by_key(|a| a.to_lowercase());
+
+        sorted_vec
+    }
+
+    pub fn ns_list(&self) -> Vec<String> {
+        let list = Self::eval_rstring(&self.system, "(mu:ns-map)".to_string());
+
+        self.list_to_sorted_vec(list)
+    }
+
+    pub fn ns_symbols(&self, ns: &str) -> Vec<String> {
+        let list = Self::eval_rstring(&self.system, format!("(mu:ns-syms :list {})", ns));
+
+        self.list_to_sorted_vec(list)
+    }
 }
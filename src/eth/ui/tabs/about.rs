@@ -11,11 +11,16 @@ use {
         super::{Core, Environment},
         window::{Message, Tab},
     },
+    super::{
+        super::widgets::terminal::{Line, Span, Terminal},
+        selection::Selection,
+    },
     iced::{
         alignment::{Horizontal, Vertical},
+        theme,
         widget::{
-            column, container, horizontal_rule, scrollable, text, Column, Container, Row, Space,
-            Text,
+            button, column, container, horizontal_rule, scrollable, text, Column, Container, Row,
+            Space, Text,
         },
         Alignment, Element, Length, Renderer,
     },
@@ -27,15 +32,38 @@ use {
 
 pub struct SysCons {
     text: RwLock<Vec<String>>,
+    selection: Selection,
 }
 
 impl SysCons {
     pub fn new() -> Self {
         SysCons {
             text: RwLock::new(Vec::<String>::new()),
+            selection: Selection::new(),
         }
     }
 
+    pub fn select(&self, line: usize, column: usize, extend: bool) {
+        self.selection.select((line, column), extend)
+    }
+
+    pub fn drag(&self, line: usize, column: usize) {
+        self.selection.drag((line, column))
+    }
+
+    pub fn selection(&self) -> Option<String> {
+        let (first, last) = self.selection.lines()?;
+        let text = self.text.read().unwrap();
+        let lines = text
+            .get(first..=last)?
+            .iter()
+            .zip(first..)
+            .filter_map(|(line, nth)| self.selection.text(nth, line))
+            .collect::<Vec<_>>();
+
+        Some(lines.join("\n"))
+    }
+
     pub fn log(&self, message: String) {
         let mut text = self.text.write().unwrap();
         let now = chrono::Utc::now();
@@ -79,6 +107,18 @@ impl AboutTab {
         self.console.log(message);
     }
 
+    pub fn select(&self, line: usize, column: usize, extend: bool) {
+        self.console.select(line, column, extend)
+    }
+
+    pub fn drag(&self, line: usize, column: usize) {
+        self.console.drag(line, column)
+    }
+
+    pub fn selection(&self) -> Option<String> {
+        self.console.selection()
+    }
+
     pub fn update(&mut self, _: &Environment, message: AboutMessage) {
         match message {
             AboutMessage::Refresh => {
@@ -90,7 +130,7 @@ impl AboutTab {
         }
     }
 
-    fn system_info(&self, width: i32, height: i32) -> Element<AboutMessage> {
+    fn system_info(&self, width: i32, height: i32) -> Element<Message> {
         let content: Element<_> = column![
             text("system".to_string()).size(20),
             horizontal_rule(1),
@@ -115,7 +155,7 @@ impl AboutTab {
             .into()
     }
 
-    fn mu_info(&self, env: &Environment, width: i32, height: i32) -> Element<AboutMessage> {
+    fn mu_info(&self, env: &Environment, width: i32, height: i32) -> Element<Message> {
         let content: Element<_> = column![
             text("mu".to_string()).size(20),
             horizontal_rule(1),
@@ -143,7 +183,7 @@ impl AboutTab {
     }
 
     pub fn view(&self, env: &Environment) -> Element<'_, Message, Renderer> {
-        let content: Element<'_, AboutMessage> = Container::new(
+        Container::new(
             Column::new()
                 .align_items(Alignment::Start)
                 .max_width(800)
@@ -160,16 +200,33 @@ impl AboutTab {
         )
         .align_x(Horizontal::Left)
         .align_y(Vertical::Top)
-        .into();
-
-        content.map(Message::About)
+        .into()
     }
 
-    fn console(&self, width: i32, height: i32) -> Element<AboutMessage> {
+    // the log is as tall as its lines, selection goes through the window,
+    // which tracks shift
+    fn console(&self, width: i32, height: i32) -> Element<Message> {
+        let lines = self
+            .console
+            .text
+            .read()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .map(|(nth, line)| Line {
+                id: nth,
+                spans: vec![Span::plain(line)],
+                selected: self.console.selection.columns(nth),
+                stamp: None,
+            })
+            .collect::<Vec<_>>();
+
         let content = column![
             text("console log"),
             horizontal_rule(1),
-            text(self.console.contents().unwrap()).size(15)
+            Terminal::new(lines, usize::MAX)
+                .size(15.0)
+                .on_select(Message::Select)
         ]
         .padding(20)
         .width(width as f32)
@@ -0,0 +1,355 @@
+//  SPDX-FileCopyrightText: Copyright 2023 James M. Putnam (putnamjm.design@gmail.com)
+//  SPDX-License-Identifier: MIT
+
+// ANSI/VT100 escape sequences
+//
+#![allow(clippy::new_without_default)]
+#![allow(dead_code)]
+use {super::tty::Span, iced::Color};
+
+// what the tty should do for the chars fed so far
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Print(char),
+    CarriageReturn,
+    LineFeed,
+    Backspace,
+    CursorUp(usize),
+    CursorDown(usize),
+    CursorForward(usize),
+    CursorBack(usize),
+    // zero based column, and row and column
+    CursorColumn(usize),
+    CursorPosition(usize, usize),
+    // 0 to the end, 1 to the start, 2 all of it
+    EraseLine(usize),
+    EraseScreen(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Ink {
+    Index(u8),
+    Rgb(Color),
+}
+
+// SGR state
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Style {
+    foreground: Option<Ink>,
+    background: Option<Ink>,
+    bold: bool,
+    underline: bool,
+    inverse: bool,
+}
+
+impl Style {
+    const PALETTE: [(u8, u8, u8); 16] = [
+        (0x00, 0x00, 0x00),
+        (0xcd, 0x00, 0x00),
+        (0x00, 0xcd, 0x00),
+        (0xcd, 0xcd, 0x00),
+        (0x00, 0x00, 0xee),
+        (0xcd, 0x00, 0xcd),
+        (0x00, 0xcd, 0xcd),
+        (0xe5, 0xe5, 0xe5),
+        (0x7f, 0x7f, 0x7f),
+        (0xff, 0x00, 0x00),
+        (0x00, 0xff, 0x00),
+        (0xff, 0xff, 0x00),
+        (0x5c, 0x5c, 0xff),
+        (0xff, 0x00, 0xff),
+        (0x00, 0xff, 0xff),
+        (0xff, 0xff, 0xff),
+    ];
+
+    // the xterm 256 color table, 16-231 are a 6x6x6 cube, the rest grays
+    fn indexed(index: u8) -> Color {
+        match index {
+            0..=15 => {
+                let (r, g, b) = Self::PALETTE[index as usize];
+                Color::from_rgb8(r, g, b)
+            }
+            16..=231 => {
+                let cube = index - 16;
+                let level = |n: u8| if n == 0 { 0 } else { 55 + n * 40 };
+
+                Color::from_rgb8(level(cube / 36), level(cube / 6 % 6), level(cube % 6))
+            }
+            _ => {
+                let gray = 8 + (index - 232) * 10;
+
+                Color::from_rgb8(gray, gray, gray)
+            }
+        }
+    }
+
+    // bold brightens the eight basic colors
+    fn color(ink: Option<Ink>, bold: bool) -> Option<Color> {
+        match ink? {
+            Ink::Index(index) if bold && index < 8 => Some(Self::indexed(index + 8)),
+            Ink::Index(index) => Some(Self::indexed(index)),
+            Ink::Rgb(color) => Some(color),
+        }
+    }
+
+    pub fn span(&self, text: &str) -> Span {
+        let foreground = Self::color(self.foreground, self.bold);
+        let background = Self::color(self.background, false);
+
+        let (color, background) = if self.inverse {
+            (
+                Some(background.unwrap_or(Color::WHITE)),
+                Some(foreground.unwrap_or(Color::BLACK)),
+            )
+        } else {
+            (foreground, background)
+        };
+
+        Span {
+            text: text.to_string(),
+            color,
+            background,
+            underline: self.underline,
+            link: None,
+        }
+    }
+
+    // 38;5;n and 38;2;r;g;b take their arguments from the rest of the params
+    fn extended(params: &mut std::slice::Iter<usize>) -> Option<Ink> {
+        match params.next()? {
+            5 => Some(Ink::Index(*params.next()? as u8)),
+            2 => {
+                let r = *params.next()? as u8;
+                let g = *params.next()? as u8;
+                let b = *params.next()? as u8;
+
+                Some(Ink::Rgb(Color::from_rgb8(r, g, b)))
+            }
+            _ => None,
+        }
+    }
+
+    fn sgr(&mut self, params: &[usize]) {
+        if params.is_empty() {
+            *self = Style::default();
+            return;
+        }
+
+        let mut params = params.iter();
+        while let Some(param) = params.next() {
+            match param {
+                0 => *self = Style::default(),
+                1 => self.bold = true,
+                4 => self.underline = true,
+                7 => self.inverse = true,
+                22 => self.bold = false,
+                24 => self.underline = false,
+                27 => self.inverse = false,
+                30..=37 => self.foreground = Some(Ink::Index((param - 30) as u8)),
+                38 => self.foreground = Self::extended(&mut params),
+                39 => self.foreground = None,
+                40..=47 => self.background = Some(Ink::Index((param - 40) as u8)),
+                48 => self.background = Self::extended(&mut params),
+                49 => self.background = None,
+                90..=97 => self.foreground = Some(Ink::Index((param - 90 + 8) as u8)),
+                100..=107 => self.background = Some(Ink::Index((param - 100 + 8) as u8)),
+                _ => (),
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Ground,
+    Escape,
+    Csi,
+}
+
+// sequences split across writes pick up where they left off
+#[derive(Debug)]
+pub struct Parser {
+    state: State,
+    params: String,
+    pub style: Style,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser {
+    pub fn new() -> Self {
+        Parser {
+            state: State::Ground,
+            params: String::new(),
+            style: Style::default(),
+        }
+    }
+
+    fn params(&self) -> Vec<usize> {
+        if self.params.is_empty() {
+            return Vec::new();
+        }
+
+        self.params
+            .split(';')
+            .map(|param| param.parse::<usize>().unwrap_or(0))
+            .collect()
+    }
+
+    // unsupported sequences are dropped
+    fn csi(&mut self, ch: char) -> Option<Action> {
+        let params = self.params();
+        let count = params.first().copied().unwrap_or(1).max(1);
+        let mode = params.first().copied().unwrap_or(0);
+
+        match ch {
+            'A' => Some(Action::CursorUp(count)),
+            'B' => Some(Action::CursorDown(count)),
+            'C' => Some(Action::CursorForward(count)),
+            'D' => Some(Action::CursorBack(count)),
+            'G' => Some(Action::CursorColumn(count - 1)),
+            'H' | 'f' => {
+                let row = params.first().copied().unwrap_or(1).max(1);
+                let column = params.get(1).copied().unwrap_or(1).max(1);
+
+                Some(Action::CursorPosition(row - 1, column - 1))
+            }
+            'J' => Some(Action::EraseScreen(mode)),
+            'K' => Some(Action::EraseLine(mode)),
+            'm' => {
+                self.style.sgr(&params);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    pub fn feed(&mut self, ch: char) -> Option<Action> {
+        match self.state {
+            State::Ground => match ch {
+                '\u{1b}' => {
+                    self.state = State::Escape;
+                    None
+                }
+                '\r' => Some(Action::CarriageReturn),
+                '\n' => Some(Action::LineFeed),
+                '\u{8}' => Some(Action::Backspace),
+                '\u{7}' => None,
+                _ => Some(Action::Print(ch)),
+            },
+            State::Escape => {
+                self.state = State::Ground;
+                match ch {
+                    '[' => {
+                        self.params.clear();
+                        self.state = State::Csi;
+                        None
+                    }
+                    'c' => {
+                        self.style = Style::default();
+                        Some(Action::EraseScreen(2))
+                    }
+                    _ => None,
+                }
+            }
+            State::Csi => match ch {
+                '0'..='9' | ';' => {
+                    self.params.push(ch);
+                    None
+                }
+                // private mode markers like ?25h
+                '?' | '>' | '=' => None,
+                '\u{40}'..='\u{7e}' => {
+                    self.state = State::Ground;
+                    self.csi(ch)
+                }
+                _ => {
+                    self.state = State::Ground;
+                    None
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Action, Ink, Parser, Style};
+
+    fn feed(parser: &mut Parser, text: &str) -> Vec<Action> {
+        text.chars().filter_map(|ch| parser.feed(ch)).collect()
+    }
+
+    #[test]
+    fn sgr() {
+        let mut parser = Parser::new();
+
+        assert_eq!(feed(&mut parser, "\u{1b}[1;4;31;42m"), vec![]);
+        assert!(parser.style.bold);
+        assert!(parser.style.underline);
+        assert_eq!(parser.style.foreground, Some(Ink::Index(1)));
+        assert_eq!(parser.style.background, Some(Ink::Index(2)));
+
+        // bold brightens the foreground
+        let span = parser.style.span("x");
+        assert_eq!(span.color, Some(Style::indexed(9)));
+        assert_eq!(span.background, Some(Style::indexed(2)));
+        assert!(span.underline);
+
+        feed(&mut parser, "\u{1b}[38;5;196;48;2;1;2;3m");
+        assert_eq!(parser.style.foreground, Some(Ink::Index(196)));
+        assert_eq!(
+            parser.style.background,
+            Some(Ink::Rgb(iced::Color::from_rgb8(1, 2, 3)))
+        );
+    }
+
+    #[test]
+    fn reset() {
+        let mut parser = Parser::new();
+
+        feed(&mut parser, "\u{1b}[1;31m");
+        feed(&mut parser, "\u{1b}[0m");
+        assert_eq!(parser.style, Style::default());
+
+        feed(&mut parser, "\u{1b}[7;32m");
+        feed(&mut parser, "\u{1b}[m");
+        assert_eq!(parser.style, Style::default());
+
+        feed(&mut parser, "\u{1b}[4;33m\u{1b}[24;39m");
+        assert_eq!(parser.style, Style::default());
+    }
+
+    #[test]
+    fn split_writes() {
+        let mut parser = Parser::new();
+
+        assert_eq!(feed(&mut parser, "a\u{1b}"), vec![Action::Print('a')]);
+        assert_eq!(feed(&mut parser, "[3"), vec![]);
+        assert_eq!(feed(&mut parser, "2mb"), vec![Action::Print('b')]);
+        assert_eq!(parser.style.foreground, Some(Ink::Index(2)));
+
+        assert_eq!(feed(&mut parser, "\u{1b}[5;"), vec![]);
+        assert_eq!(feed(&mut parser, "10H"), vec![Action::CursorPosition(4, 9)]);
+    }
+
+    #[test]
+    fn controls() {
+        let mut parser = Parser::new();
+
+        assert_eq!(
+            feed(&mut parser, "\r\n\u{8}\u{7}\u{1b}[2K\u{1b}[3D\u{1b}[?25h"),
+            vec![
+                Action::CarriageReturn,
+                Action::LineFeed,
+                Action::Backspace,
+                Action::EraseLine(2),
+                Action::CursorBack(3),
+            ]
+        );
+    }
+}
@@ -0,0 +1,305 @@
+//  SPDX-FileCopyrightText: Copyright 2023 James M. Putnam (putnamjm.design@gmail.com)
+//  SPDX-License-Identifier: MIT
+
+// screen and scrollback lines
+//
+#![allow(dead_code)]
+use {
+    super::tty::Span,
+    std::{collections::VecDeque, mem, time::SystemTime},
+};
+
+#[derive(Debug, Clone, Default)]
+struct BufferLine {
+    spans: Vec<Span>,
+    // when the line got its first text
+    time: Option<SystemTime>,
+    bytes: usize,
+}
+
+impl BufferLine {
+    fn size(spans: &[Span]) -> usize {
+        spans
+            .iter()
+            .map(|span| mem::size_of::<Span>() + span.text.len())
+            .sum()
+    }
+}
+
+// a ring of lines, the last rows of them are the screen and the rest is
+// scrollback. edits touch one line, and the oldest scrollback lines are
+// dropped when there are too many of them or they take too much memory.
+#[derive(Debug, Default)]
+pub struct TextBuffer {
+    lines: VecDeque<BufferLine>,
+    rows: usize,
+    scrollback: usize,
+    max_bytes: usize,
+    bytes: usize,
+    // lines trimmed so far, line n is line n + dropped of all time
+    dropped: usize,
+    timestamps: bool,
+}
+
+impl TextBuffer {
+    const MAX_BYTES: usize = 4 * 1024 * 1024;
+
+    pub fn new(rows: usize, scrollback: usize) -> Self {
+        let rows = rows.max(1);
+
+        TextBuffer {
+            lines: (0..rows).map(|_| BufferLine::default()).collect(),
+            rows,
+            scrollback,
+            max_bytes: Self::MAX_BYTES,
+            bytes: 0,
+            dropped: 0,
+            timestamps: false,
+        }
+    }
+
+    pub fn timestamps(self, timestamps: bool) -> Self {
+        TextBuffer { timestamps, ..self }
+    }
+
+    pub fn max_bytes(self, max_bytes: usize) -> Self {
+        TextBuffer { max_bytes, ..self }
+    }
+
+    pub fn line_text(line: &[Span]) -> String {
+        line.iter().map(|span| span.text.as_str()).collect()
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    // all of the lines, scrollback and screen
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.iter().all(|line| line.spans.is_empty())
+    }
+
+    // lines in the scrollback
+    pub fn history(&self) -> usize {
+        self.lines.len() - self.rows
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    // lines are counted from the oldest scrollback line
+    pub fn line(&self, nth: usize) -> Option<&[Span]> {
+        self.lines.get(nth).map(|line| line.spans.as_slice())
+    }
+
+    pub fn time(&self, nth: usize) -> Option<SystemTime> {
+        self.lines.get(nth).and_then(|line| line.time)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &[Span]> {
+        self.lines.iter().map(|line| line.spans.as_slice())
+    }
+
+    // rows are counted from the top of the screen
+    pub fn row(&self, row: usize) -> &[Span] {
+        &self.lines[self.history() + row].spans
+    }
+
+    pub fn screen(&self) -> impl Iterator<Item = &[Span]> {
+        self.iter().skip(self.history())
+    }
+
+    // edit a screen row in place, only that line is re-measured
+    pub fn update_row<T>(&mut self, row: usize, edit: impl FnOnce(&mut Vec<Span>) -> T) -> T {
+        let nth = self.history() + row;
+        let timestamps = self.timestamps;
+        let line = &mut self.lines[nth];
+
+        let value = edit(&mut line.spans);
+        let bytes = BufferLine::size(&line.spans);
+
+        if timestamps && line.time.is_none() && !line.spans.is_empty() {
+            line.time = Some(SystemTime::now())
+        }
+
+        self.bytes = self.bytes - line.bytes + bytes;
+        line.bytes = bytes;
+        self.trim();
+
+        value
+    }
+
+    pub fn set_row(&mut self, row: usize, spans: Vec<Span>) {
+        self.update_row(row, |line| *line = spans)
+    }
+
+    // append to the last row
+    pub fn append(&mut self, span: Span) {
+        self.append_to(self.rows - 1, span)
+    }
+
+    // append to a row, merging with its last span when the styles match
+    pub fn append_to(&mut self, row: usize, span: Span) {
+        self.update_row(row, |line| match line.last_mut() {
+            Some(tail) if tail.same_style(&span) => tail.text.push_str(&span.text),
+            _ => line.push(span),
+        })
+    }
+
+    // drop the last char of the last row
+    pub fn backspace(&mut self) -> bool {
+        let last = self.rows - 1;
+
+        self.update_row(last, |line| {
+            while let Some(span) = line.last_mut() {
+                if span.text.pop().is_some() {
+                    if span.text.is_empty() {
+                        line.pop();
+                    }
+                    return true;
+                }
+                line.pop();
+            }
+
+            false
+        })
+    }
+
+    // move the top row into the scrollback and open a new last row
+    pub fn scroll(&mut self) {
+        self.lines.push_back(BufferLine::default());
+        self.trim()
+    }
+
+    // blank the screen, the scrollback is kept
+    pub fn clear(&mut self) {
+        for row in 0..self.rows {
+            self.update_row(row, |line| line.clear())
+        }
+
+        let history = self.history();
+        for line in self.lines.iter_mut().skip(history) {
+            line.time = None
+        }
+    }
+
+    // the screen as text
+    pub fn contents(&self) -> String {
+        self.screen()
+            .map(Self::line_text)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // the screen is never trimmed
+    fn trim(&mut self) {
+        while self.history() > self.scrollback
+            || (self.history() > 0 && self.bytes > self.max_bytes)
+        {
+            match self.lines.pop_front() {
+                Some(line) => {
+                    self.bytes -= line.bytes;
+                    self.dropped += 1
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Span, TextBuffer};
+
+    fn write(buffer: &mut TextBuffer, text: &str) {
+        buffer.append(Span::plain(text))
+    }
+
+    #[test]
+    fn scroll() {
+        let mut buffer = TextBuffer::new(2, 2);
+
+        for line in ["a", "b", "c", "d"] {
+            write(&mut buffer, line);
+            buffer.scroll()
+        }
+
+        assert_eq!(buffer.contents(), "d\n");
+        assert_eq!(buffer.history(), 2);
+        assert_eq!(buffer.len(), 4);
+        assert_eq!(buffer.dropped(), 2);
+        assert_eq!(TextBuffer::line_text(buffer.line(0).unwrap()), "b");
+        assert_eq!(TextBuffer::line_text(buffer.line(1).unwrap()), "c");
+    }
+
+    #[test]
+    fn scroll_memory_cap() {
+        let mut buffer = TextBuffer::new(1, 100).max_bytes(1);
+
+        write(&mut buffer, "old");
+        buffer.scroll();
+        write(&mut buffer, "new");
+
+        // scrollback goes first, the screen is kept
+        assert_eq!(buffer.history(), 0);
+        assert_eq!(buffer.contents(), "new");
+    }
+
+    #[test]
+    fn clear() {
+        let mut buffer = TextBuffer::new(2, 10);
+
+        write(&mut buffer, "kept");
+        buffer.scroll();
+        buffer.scroll();
+        let bytes = buffer.bytes();
+        write(&mut buffer, "screen");
+        buffer.clear();
+
+        assert_eq!(buffer.contents(), "\n");
+        assert_eq!(buffer.history(), 2);
+        assert_eq!(TextBuffer::line_text(buffer.line(1).unwrap()), "kept");
+        assert_eq!(buffer.bytes(), bytes);
+    }
+
+    #[test]
+    fn backspace() {
+        let mut buffer = TextBuffer::new(1, 0);
+
+        write(&mut buffer, "ab");
+        buffer.append(Span::link("c", 0));
+        let bytes = buffer.bytes();
+
+        assert!(buffer.backspace());
+        assert_eq!(buffer.contents(), "ab");
+        assert_eq!(buffer.row(0).len(), 1);
+        assert!(buffer.bytes() < bytes);
+
+        assert!(buffer.backspace());
+        assert!(buffer.backspace());
+        assert!(!buffer.backspace());
+        assert_eq!(buffer.contents(), "");
+        assert_eq!(buffer.bytes(), 0);
+    }
+
+    #[test]
+    fn timestamps() {
+        let mut buffer = TextBuffer::new(1, 1).timestamps(true);
+
+        assert!(buffer.time(0).is_none());
+        write(&mut buffer, "x");
+        assert!(buffer.time(0).is_some());
+        buffer.scroll();
+        assert!(buffer.time(0).is_some());
+        assert!(buffer.time(1).is_none());
+    }
+}
@@ -0,0 +1,125 @@
+//  SPDX-FileCopyrightText: Copyright 2023 James M. Putnam (putnamjm.design@gmail.com)
+//  SPDX-License-Identifier: MIT
+
+// listener symbol completion
+//
+#![allow(clippy::new_without_default)]
+#![allow(dead_code)]
+use {crate::Core, std::collections::HashMap};
+
+// namespace and symbol names, fetched lazily and dropped after evaluations
+#[derive(Debug, Default)]
+pub struct Completer {
+    namespaces: Option<Vec<String>>,
+    symbols: HashMap<String, Vec<String>>,
+}
+
+impl Completer {
+    const KEYWORDS: &'static [&'static str] = &[
+        ":byte", ":char", ":cons", ":fixnum", ":float", ":func", ":if", ":input", ":keyword",
+        ":lambda", ":list", ":macro", ":output", ":quote", ":stream", ":string", ":struct",
+        ":symbol", ":t", ":vector",
+    ];
+
+    pub fn new() -> Self {
+        Completer {
+            namespaces: None,
+            symbols: HashMap::new(),
+        }
+    }
+
+    pub fn invalidate(&mut self) {
+        self.namespaces = None;
+        self.symbols.clear()
+    }
+
+    // namespace names without the keyword colon
+    pub fn namespaces(&mut self, core: &Core) -> &Vec<String> {
+        self.namespaces.get_or_insert_with(|| {
+            core.ns_list()
+                .iter()
+                .map(|ns| ns.trim_start_matches(':').to_string())
+                .collect()
+        })
+    }
+
+    pub fn symbols(&mut self, core: &Core, ns: &str) -> &Vec<String> {
+        self.symbols
+            .entry(ns.to_string())
+            .or_insert_with(|| core.ns_symbols(&format!(":{}", ns)))
+    }
+
+    // candidates for prefix, sorted. unqualified names are read in ns, so
+    // its symbols complete along with the namespace names
+    pub fn complete(&mut self, core: &Core, ns: &str, prefix: &str) -> Vec<String> {
+        let mut candidates = match prefix.split_once(':') {
+            Some(("", key)) => {
+                let mut keywords = Self::KEYWORDS
+                    .iter()
+                    .map(|keyword| keyword.to_string())
+                    .collect::<Vec<_>>();
+
+                keywords.extend(self.namespaces(core).iter().map(|ns| format!(":{}", ns)));
+                keywords
+                    .into_iter()
+                    .filter(|keyword| keyword[1..].starts_with(key))
+                    .collect::<Vec<_>>()
+            }
+            Some((ns, name)) => {
+                if !self.namespaces(core).iter().any(|known| known == ns) {
+                    return vec![];
+                }
+
+                self.symbols(core, ns)
+                    .iter()
+                    .filter(|symbol| symbol.starts_with(name))
+                    .map(|symbol| format!("{}:{}", ns, symbol))
+                    .collect::<Vec<_>>()
+            }
+            None => {
+                let mut names = self
+                    .namespaces(core)
+                    .iter()
+                    .filter(|ns| ns.starts_with(prefix))
+                    .map(|ns| format!("{}:", ns))
+                    .collect::<Vec<_>>();
+
+                if self.namespaces(core).iter().any(|known| known == ns) {
+                    names.extend(
+                        self.symbols(core, ns)
+                            .iter()
+                            .filter(|symbol| symbol.starts_with(prefix))
+                            .cloned(),
+                    )
+                }
+
+                names
+            }
+        };
+
+        candidates.sort();
+        candidates.dedup();
+        candidates
+    }
+
+    pub fn common_prefix(candidates: &[String]) -> String {
+        match candidates.split_first() {
+            None => String::new(),
+            Some((first, rest)) => {
+                let mut prefix = first.chars().collect::<Vec<_>>();
+
+                for candidate in rest {
+                    let common = prefix
+                        .iter()
+                        .zip(candidate.chars())
+                        .take_while(|(a, b)| **a == *b)
+                        .count();
+
+                    prefix.truncate(common)
+                }
+
+                prefix.into_iter().collect()
+            }
+        }
+    }
+}
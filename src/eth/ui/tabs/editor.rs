@@ -0,0 +1,140 @@
+//  SPDX-FileCopyrightText: Copyright 2023 James M. Putnam (putnamjm.design@gmail.com)
+//  SPDX-License-Identifier: MIT
+
+// listener line editor
+//
+#![allow(clippy::new_without_default)]
+#![allow(dead_code)]
+
+#[derive(Debug, Default, Clone)]
+pub struct LineEditor {
+    buffer: Vec<char>,
+    cursor: usize,
+}
+
+impl LineEditor {
+    pub fn new() -> Self {
+        LineEditor {
+            buffer: Vec::new(),
+            cursor: 0,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.buffer.iter().collect()
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.cursor = 0
+    }
+
+    // replace the line, cursor at end
+    pub fn set(&mut self, text: &str) {
+        self.buffer = text.chars().collect();
+        self.cursor = self.buffer.len()
+    }
+
+    pub fn insert(&mut self, ch: char) {
+        self.buffer.insert(self.cursor, ch);
+        self.cursor += 1
+    }
+
+    pub fn insert_str(&mut self, str: &str) {
+        for ch in str.chars() {
+            self.insert(ch)
+        }
+    }
+
+    // delete the character before the cursor
+    pub fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            self.buffer.remove(self.cursor);
+        }
+    }
+
+    // delete the character under the cursor
+    pub fn delete(&mut self) {
+        if self.cursor < self.buffer.len() {
+            self.buffer.remove(self.cursor);
+        }
+    }
+
+    // delete from the cursor to the end of the line
+    pub fn kill(&mut self) -> String {
+        self.buffer.drain(self.cursor..).collect()
+    }
+
+    pub fn left(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1
+        }
+    }
+
+    pub fn right(&mut self) {
+        if self.cursor < self.buffer.len() {
+            self.cursor += 1
+        }
+    }
+
+    pub fn home(&mut self) {
+        self.cursor = 0
+    }
+
+    pub fn end(&mut self) {
+        self.cursor = self.buffer.len()
+    }
+
+    fn is_word(ch: char) -> bool {
+        !(ch.is_whitespace() || "()'`,\"".contains(ch))
+    }
+
+    // the symbol fragment before the cursor
+    pub fn prefix(&self) -> String {
+        let start = self.buffer[0..self.cursor]
+            .iter()
+            .rposition(|ch| !Self::is_word(*ch))
+            .map_or(0, |nth| nth + 1);
+
+        self.buffer[start..self.cursor].iter().collect()
+    }
+
+    pub fn replace_prefix(&mut self, text: &str) {
+        for _ in 0..self.prefix().chars().count() {
+            self.backspace()
+        }
+
+        self.insert_str(text)
+    }
+
+    pub fn word_left(&mut self) {
+        while self.cursor > 0 && !Self::is_word(self.buffer[self.cursor - 1]) {
+            self.cursor -= 1
+        }
+
+        while self.cursor > 0 && Self::is_word(self.buffer[self.cursor - 1]) {
+            self.cursor -= 1
+        }
+    }
+
+    pub fn word_right(&mut self) {
+        let len = self.buffer.len();
+
+        while self.cursor < len && !Self::is_word(self.buffer[self.cursor]) {
+            self.cursor += 1
+        }
+
+        while self.cursor < len && Self::is_word(self.buffer[self.cursor]) {
+            self.cursor += 1
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//  SPDX-FileCopyrightText: Copyright 2023 James M. Putnam (putnamjm.design@gmail.com)
+//  SPDX-License-Identifier: MIT
+
+// listener command history
+//
+#![allow(dead_code)]
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Default)]
+pub struct History {
+    entries: Vec<String>,
+    limit: usize,
+    path: PathBuf,
+    nth: Option<usize>,
+}
+
+impl History {
+    // a missing or damaged history file starts an empty history
+    pub fn load(path: &Path, limit: usize) -> Self {
+        let mut history = History {
+            entries: Self::read(path),
+            limit,
+            path: path.to_path_buf(),
+            nth: None,
+        };
+
+        history.truncate();
+        history
+    }
+
+    fn read(path: &Path) -> Vec<String> {
+        match File::open(path) {
+            Err(_) => Vec::new(),
+            Ok(file) => match serde_json::from_reader::<_, Vec<String>>(BufReader::new(file)) {
+                Err(_) => Vec::new(),
+                Ok(entries) => entries,
+            },
+        }
+    }
+
+    // sessions share the file, so entries other sessions saved since we
+    // loaded are kept, ahead of ours. our own entries aren't changed, the
+    // others only show up in sessions opened later
+    pub fn save(&self) -> std::io::Result<()> {
+        let mut entries = Self::read(&self.path);
+
+        entries.retain(|entry| !self.entries.contains(entry));
+        entries.extend(self.entries.iter().cloned());
+        if entries.len() > self.limit {
+            entries.drain(0..entries.len() - self.limit);
+        }
+
+        let file = File::create(&self.path)?;
+
+        serde_json::to_writer_pretty(BufWriter::new(file), &entries)?;
+        Ok(())
+    }
+
+    fn truncate(&mut self) {
+        if self.entries.len() > self.limit {
+            self.entries.drain(0..self.entries.len() - self.limit);
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, nth: usize) -> Option<&str> {
+        self.entries.get(nth).map(|entry| entry.as_str())
+    }
+
+    // add an entry, an earlier duplicate moves to the end. the entry is
+    // kept for this session even if the file can't be written
+    pub fn push(&mut self, entry: &str) -> std::io::Result<()> {
+        self.nth = None;
+
+        let entry = entry.trim();
+        if entry.is_empty() {
+            return Ok(());
+        }
+
+        self.entries.retain(|previous| previous != entry);
+        self.entries.push(entry.to_string());
+        self.truncate();
+
+        self.save()
+    }
+
+    // leave history navigation
+    pub fn reset(&mut self) {
+        self.nth = None
+    }
+
+    pub fn is_navigating(&self) -> bool {
+        self.nth.is_some()
+    }
+
+    pub fn older(&mut self) -> Option<&str> {
+        let nth = match self.nth {
+            None if self.entries.is_empty() => return None,
+            None => self.entries.len() - 1,
+            Some(0) => 0,
+            Some(nth) => nth - 1,
+        };
+
+        self.nth = Some(nth);
+        self.get(nth)
+    }
+
+    // None when we've moved past the newest entry
+    pub fn newer(&mut self) -> Option<&str> {
+        match self.nth {
+            None => None,
+            Some(nth) if nth + 1 >= self.entries.len() => {
+                self.nth = None;
+                None
+            }
+            Some(nth) => {
+                self.nth = Some(nth + 1);
+                self.get(nth + 1)
+            }
+        }
+    }
+
+    // search backwards from before for an entry containing pattern
+    pub fn search(&self, pattern: &str, before: usize) -> Option<usize> {
+        self.entries[0..before.min(self.entries.len())]
+            .iter()
+            .rposition(|entry| entry.contains(pattern))
+    }
+}
@@ -7,41 +7,103 @@
 #![allow(dead_code)]
 #![allow(unused_imports)]
 use {
-    super::super::{
-        super::{Core, Environment},
-        window::{Message, Tab},
+    super::{
+        super::{
+            super::{Core, Environment},
+            window::{Message, Tab},
+        },
+        syntax,
     },
     iced::{
         alignment::{Horizontal, Vertical},
+        keyboard::{Event::KeyPressed, KeyCode},
         theme,
         widget::{button, column, row, scrollable},
-        widget::{container, horizontal_rule, text},
+        widget::{container, horizontal_rule, text, text_input},
         widget::{Column, Container, Row, Space, Text},
-        Alignment, Background, Color, Element, Font, Length, Renderer,
+        Alignment, Background, Color, Element, Event, Font, Length, Renderer,
     },
     iced_aw::{
         selection_list::{selection_list, SelectionList, SelectionListStyles},
         tab_bar::TabLabel,
     },
     mu::{Condition, Exception, Mu, Result, System as MuSystem, Tag},
+    std::time::{Duration, Instant},
     sysinfo::{System, SystemExt},
 };
 
+// an inspected object and the tag key that reached it
+type Level = (Option<String>, String);
+
+// the list the arrow keys move through
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Filter {
+    Namespaces,
+    Symbols,
+}
+
+// a node of the inspect: tree, children are loaded when it's first expanded
+#[derive(Debug, Clone)]
+struct Node {
+    key: String,
+    repr: String,
+    kind: String,
+    // the :type and :size lines from eth:inspect-repr
+    summary: String,
+    value: String,
+    // tag keys and reprs of the children, and the ones loaded so far
+    entries: Option<Vec<(String, String)>>,
+    children: Vec<Node>,
+    expanded: bool,
+    // when a refresh last saw the value change
+    changed: Option<Instant>,
+}
+
+impl Node {
+    const KINDS: [&'static str; 4] = [":cons", ":vector", ":struct", ":ns"];
+
+    fn is_expandable(&self) -> bool {
+        Self::KINDS.contains(&self.kind.as_str())
+    }
+
+    fn unloaded(&self) -> usize {
+        match &self.entries {
+            Some(entries) => entries.len() - self.children.len(),
+            None => 0,
+        }
+    }
+}
+
 // inspector
 pub struct InspectorTab {
+    expression: String,
+    expression_error: Option<String>,
+    filter: Filter,
+    inspect_forward: Vec<Level>,
+    inspect_path: Vec<Level>,
     inspect_tag_key: Option<String>,
-    inspect_tag_keys: Option<Vec<String>>,
     inspect_tag_repr: Option<String>,
-    inspect_tag_reprs: Option<Vec<String>>,
+    inspect_tree: Option<Node>,
+    // messages for the about console
+    log: Vec<String>,
     namespace: Option<String>,
+    namespace_filter: String,
+    namespace_matches: Vec<String>,
     namespace_symbols: Option<Vec<String>>,
     namespaces: Option<Vec<String>>,
+    refreshed: Option<Instant>,
     symbol: Option<String>,
+    symbol_changed: Option<Instant>,
+    symbol_edit: String,
+    symbol_edit_error: Option<String>,
+    symbol_filter: String,
+    symbol_matches: Vec<String>,
     symbol_info: Option<String>,
     symbol_tag_key: Option<String>,
     symbol_tag_keys: Option<Vec<String>>,
     symbol_tag_repr: Option<String>,
     symbol_tag_reprs: Option<Vec<String>>,
+    symbol_value: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -49,30 +111,68 @@ pub enum InspectorMessage {
     SelectSymbol(String),
     SelectNamespace(String),
     SelectSymbolTagKey(String),
-    SelectInspectTagKey(String),
+    NamespaceFilter(String),
+    SymbolFilter(String),
+    EventOccurred(Event),
+    Inspect(String),
+    InspectBack,
+    InspectForward,
+    InspectCrumb(usize),
+    ToggleNode(Vec<usize>),
+    MoreNodes(Vec<usize>),
+    InspectNode(Vec<usize>),
+    Tick(Instant),
+    Expression(String),
+    EvalExpression,
+    SymbolEdit(String),
+    SetSymbol,
     Clear,
     Refresh,
 }
 
 impl InspectorTab {
+    const TREE_DEPTH: usize = 6;
+    const TREE_LIMIT: usize = 16;
+    const VALUE_WIDTH: usize = 24;
+    const REFRESH: Duration = Duration::from_secs(10);
+    const HIGHLIGHT: Duration = Duration::from_secs(2);
+
     pub fn new() -> Self {
         Self {
+            expression: String::new(),
+            expression_error: None,
+            filter: Filter::Namespaces,
+            inspect_forward: Vec::new(),
+            inspect_path: Vec::new(),
             inspect_tag_key: None,
-            inspect_tag_keys: None,
             inspect_tag_repr: None,
-            inspect_tag_reprs: None,
+            inspect_tree: None,
+            log: Vec::new(),
             namespace: None,
+            namespace_filter: String::new(),
+            namespace_matches: Vec::new(),
             namespace_symbols: None,
             namespaces: None,
+            refreshed: None,
             symbol: None,
+            symbol_changed: None,
+            symbol_edit: String::new(),
+            symbol_edit_error: None,
+            symbol_filter: String::new(),
+            symbol_matches: Vec::new(),
             symbol_info: None,
             symbol_tag_key: None,
             symbol_tag_keys: None,
             symbol_tag_repr: None,
             symbol_tag_reprs: None,
+            symbol_value: None,
         }
     }
 
+    pub fn take_log(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.log)
+    }
+
     fn trimr(value: &str) -> &str {
         let mut chars = value.chars();
         chars.next_back();
@@ -85,43 +185,72 @@ impl InspectorTab {
         chars.as_str()
     }
 
-    fn list_to_vec(env: &Environment, list: Tag) -> Vec<String> {
-        let core = env.core.as_ref().unwrap();
+    // every char of pattern in order, ignoring case
+    fn fuzzy(pattern: &str, candidate: &str) -> bool {
+        let mut chars = candidate.chars().flat_map(char::to_lowercase);
 
-        let list_str = core.system.write(list, false);
-        if core.system.mu().eq(core.nil, list) {
-            return vec![];
-        }
+        pattern
+            .chars()
+            .flat_map(char::to_lowercase)
+            .all(|ch| chars.any(|candidate| candidate == ch))
+    }
 
-        let trim = Self::triml(Self::trimr(&list_str)).to_string();
-        trim.split(' ')
-            .map(|str| str.to_string())
-            .collect::<Vec<_>>()
+    // substring matches first, then fuzzy ones
+    fn matches(list: &Option<Vec<String>>, pattern: &str) -> Vec<String> {
+        let list = match list {
+            Some(list) => list,
+            None => return Vec::new(),
+        };
+
+        let pattern = pattern.to_lowercase();
+        let (mut substrings, fuzzy): (Vec<_>, Vec<_>) = list
+            .iter()
+            .filter(|name| Self::fuzzy(&pattern, name))
+            .cloned()
+            .partition(|name| name.to_lowercase().contains(&pattern));
+
+        substrings.extend(fuzzy);
+        substrings
     }
 
-    fn list_to_sorted_vec(env: &Environment, list: Tag) -> Vec<String> {
-        let mut sorted_vec = Self::list_to_vec(env, list);
+    fn filter_lists(&mut self) {
+        self.namespace_matches = Self::matches(&self.namespaces, &self.namespace_filter);
+        self.symbol_matches = Self::matches(&self.namespace_symbols, &self.symbol_filter);
+    }
+
+    // select the next or previous match in the filtered list
+    fn step(&mut self, env: &Environment, down: bool) {
+        let (matches, selected) = match self.filter {
+            Filter::Namespaces => (&self.namespace_matches, &self.namespace),
+            Filter::Symbols => (&self.symbol_matches, &self.symbol),
+        };
+
+        if matches.is_empty() {
+            return;
+        }
 
-        sorted_vec.sort_by_key(|a| a.to_lowercase());
+        let nth = match selected
+            .as_ref()
+            .and_then(|selected| matches.iter().position(|name| name == selected))
+        {
+            Some(nth) if down => (nth + 1).min(matches.len() - 1),
+            Some(nth) => nth.saturating_sub(1),
+            None => 0,
+        };
 
-        sorted_vec
+        let name = matches[nth].clone();
+        match self.filter {
+            Filter::Namespaces => self.update(env, InspectorMessage::SelectNamespace(name)),
+            Filter::Symbols => self.update(env, InspectorMessage::SelectSymbol(name)),
+        }
     }
 
     fn fetch_ns_list(env: &Environment) -> Vec<String> {
-        let core = env.core.as_ref().unwrap();
-        let list = Core::eval_rstring(&core.system, "(mu:ns-map)".to_string());
-
-        Self::list_to_sorted_vec(env, list)
+        env.core.as_ref().unwrap().ns_list()
     }
 
     fn fetch_symbols_list(env: &Environment, ns: String) -> Vec<String> {
-        let core = env.core.as_ref().unwrap();
-        let list = Core::eval_rstring(
-            &core.system,
-            format!("(mu:ns-syms :list {})", ns).to_string(),
-        );
-
-        Self::list_to_sorted_vec(env, list)
+        env.core.as_ref().unwrap().ns_symbols(&ns)
     }
 
     fn inspect_repr(env: &Environment, repr: &String) -> String {
@@ -177,54 +306,355 @@ impl InspectorTab {
         core.system.write(inspect_str, false)
     }
 
+    // the printed value of ns:symbol, None if it's unbound
+    fn fetch_symbol_value(env: &Environment, ns: &str, symbol: &String) -> Option<String> {
+        let core = env.core.as_ref().unwrap();
+
+        core.try_eval(format!("{}:{}", Self::triml(ns), symbol))
+            .ok()
+            .map(|value| core.system.write(value, true))
+    }
+
+    // a form for the edit field that evaluates to the printed value, so
+    // setting it unchanged leaves the binding alone
+    fn edit_form(value: &Option<String>) -> String {
+        match value {
+            Some(value) if syntax::is_self_evaluating(value) => value.to_string(),
+            Some(value) => format!("(:quote {})", value),
+            None => String::new(),
+        }
+    }
+
+    // bind the selected symbol to the value of the edit form
+    fn set_symbol(&mut self, env: &Environment) {
+        let (ns, symbol) = match (&self.namespace, &self.symbol) {
+            (Some(ns), Some(symbol)) => (ns.to_string(), symbol.to_string()),
+            _ => return,
+        };
+        let core = env.core.as_ref().unwrap();
+
+        match core
+            .repr(&self.symbol_edit)
+            .and_then(|repr| core.intern_repr(&ns, &symbol, &repr))
+        {
+            Ok(_) => {
+                let old = match &self.symbol_value {
+                    Some(value) => value.to_string(),
+                    None => "unbound".to_string(),
+                };
+                let new = Self::fetch_symbol_value(env, &ns, &symbol).unwrap_or_default();
+
+                self.log.push(format!(
+                    "inspector: {}:{} {} => {}",
+                    Self::triml(&ns),
+                    symbol,
+                    old,
+                    new
+                ));
+                self.update(env, InspectorMessage::SelectSymbol(symbol))
+            }
+            Err(ex) => self.symbol_edit_error = Some(core.system.error(ex)),
+        }
+    }
+
+    // the printed object for a mu:repr vector
+    fn print_repr(env: &Environment, repr: &String) -> String {
+        let core = env.core.as_ref().unwrap();
+
+        match core.from_repr(repr) {
+            Ok(tag) => core.system.write(tag, true),
+            Err(ex) => core.system.error(ex),
+        }
+    }
+
+    // a printed value cut to width for the tree
+    fn elide(value: &str) -> String {
+        match value.char_indices().nth(Self::VALUE_WIDTH) {
+            Some((at, _)) => format!("{}...", &value[..at]),
+            None => value.to_string(),
+        }
+    }
+
+    fn load_node(env: &Environment, key: String, repr: String) -> Node {
+        let summary = Self::inspect_repr(env, &repr);
+        let kind = summary
+            .lines()
+            .next()
+            .and_then(|line| line.strip_prefix(":type "))
+            .unwrap_or("")
+            .trim()
+            .to_string();
+
+        Node {
+            key,
+            summary,
+            value: Self::print_repr(env, &repr),
+            repr,
+            kind,
+            entries: None,
+            children: Vec::new(),
+            expanded: false,
+            changed: None,
+        }
+    }
+
+    fn fetch_entries(env: &Environment, repr: &String) -> Vec<(String, String)> {
+        let keys = Self::inspect_repr_tag_keys(env, repr);
+        let reprs = Self::inspect_repr_tag_reprs(env, repr);
+
+        keys.split(';')
+            .zip(reprs.split(';'))
+            .filter(|(key, _)| !key.is_empty())
+            .map(|(key, repr)| (key.to_string(), repr.to_string()))
+            .collect()
+    }
+
+    // fetch the tag keys on first use, then load the next TREE_LIMIT children
+    fn load_children(env: &Environment, node: &mut Node) {
+        if node.entries.is_none() {
+            node.entries = Some(Self::fetch_entries(env, &node.repr))
+        }
+
+        let loaded = node.children.len();
+        let entries = node.entries.as_ref().unwrap()[loaded..]
+            .iter()
+            .take(Self::TREE_LIMIT)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for (key, repr) in entries {
+            node.children.push(Self::load_node(env, key, repr))
+        }
+    }
+
+    // reprint the loaded nodes, children whose key or object changed are reloaded
+    fn refresh_node(env: &Environment, node: &mut Node, now: Instant) {
+        let value = Self::print_repr(env, &node.repr);
+
+        if value != node.value {
+            node.value = value;
+            node.changed = Some(now)
+        }
+
+        if node.entries.is_none() {
+            return;
+        }
+
+        let entries = Self::fetch_entries(env, &node.repr);
+
+        node.children.truncate(entries.len());
+        for (child, (key, repr)) in node.children.iter_mut().zip(entries.iter()) {
+            if child.key == *key && child.repr == *repr {
+                Self::refresh_node(env, child, now)
+            } else {
+                *child = Self::load_node(env, key.to_string(), repr.to_string());
+                child.changed = Some(now)
+            }
+        }
+        node.entries = Some(entries)
+    }
+
+    fn is_recent(changed: Option<Instant>) -> bool {
+        match changed {
+            Some(at) => at.elapsed() < Self::HIGHLIGHT,
+            None => false,
+        }
+    }
+
+    // reload the lists, the selected symbol and the inspected object
+    fn refresh(&mut self, env: &Environment) {
+        let now = Instant::now();
+
+        self.namespaces = Some(Self::fetch_ns_list(env));
+        if let Some(ns) = &self.namespace {
+            self.namespace_symbols = Some(Self::fetch_symbols_list(env, ns.to_string()))
+        }
+
+        if let (Some(ns), Some(symbol)) = (self.namespace.clone(), self.symbol.clone()) {
+            let value = Self::fetch_symbol_value(env, &ns, &symbol);
+
+            if value != self.symbol_value {
+                // don't clobber an edit in progress
+                if self.symbol_edit == Self::edit_form(&self.symbol_value) {
+                    self.symbol_edit = Self::edit_form(&value)
+                }
+                self.symbol_value = value;
+                self.symbol_changed = Some(now);
+                self.load_symbol(env)
+            }
+        }
+
+        if let Some(tree) = self.inspect_tree.as_mut() {
+            tree.summary = Self::inspect_repr(env, &tree.repr);
+            Self::refresh_node(env, tree, now)
+        }
+
+        self.filter_lists();
+        self.refreshed = Some(now)
+    }
+
+    fn load_symbol(&mut self, env: &Environment) {
+        self.symbol_info = Some(Self::inspect_symbol(
+            env,
+            self.namespace.as_ref().unwrap(),
+            self.symbol.as_ref().unwrap(),
+        ));
+
+        self.symbol_tag_keys = Some(
+            Self::trimr(&Self::inspect_symbol_tag_keys(
+                env,
+                self.namespace.as_ref().unwrap(),
+                self.symbol.as_ref().unwrap(),
+            ))
+            .to_string()
+            .split(';')
+            .map(|str| str.to_string())
+            .collect::<Vec<_>>(),
+        );
+
+        self.symbol_tag_reprs = Some(
+            Self::trimr(&Self::inspect_symbol_tag_reprs(
+                env,
+                self.namespace.as_ref().unwrap(),
+                self.symbol.as_ref().unwrap(),
+            ))
+            .to_string()
+            .split(';')
+            .map(|str| str.to_string())
+            .collect::<Vec<_>>(),
+        );
+    }
+
+    // the node at path, a list of child indices from the root
+    fn node_mut(&mut self, path: &[usize]) -> Option<&mut Node> {
+        let mut node = self.inspect_tree.as_mut()?;
+
+        for nth in path {
+            node = node.children.get_mut(*nth)?
+        }
+
+        Some(node)
+    }
+
+    fn toggle_node(&mut self, env: &Environment, path: &[usize]) {
+        if path.len() >= Self::TREE_DEPTH {
+            return;
+        }
+
+        if let Some(node) = self.node_mut(path) {
+            if node.is_expandable() {
+                if node.entries.is_none() {
+                    Self::load_children(env, node)
+                }
+                node.expanded = !node.expanded
+            }
+        }
+    }
+
+    // load the inspect: pane from a mu:repr vector
+    fn load_inspect(&mut self, env: &Environment, key: Option<String>, repr: &String) {
+        self.inspect_tag_key = key;
+        self.inspect_tag_repr = Some(repr.to_string());
+
+        let key = match &self.inspect_tag_key {
+            Some(key) => key.to_string(),
+            None => "object".to_string(),
+        };
+        let mut root = Self::load_node(env, key, repr.to_string());
+
+        if root.is_expandable() {
+            Self::load_children(env, &mut root);
+            root.expanded = true
+        }
+        self.inspect_tree = Some(root)
+    }
+
+    // start a new trail at repr
+    fn inspect_root(&mut self, env: &Environment, key: Option<String>, repr: &String) {
+        self.inspect_path = vec![(key.clone(), repr.to_string())];
+        self.inspect_forward.clear();
+        self.load_inspect(env, key, repr)
+    }
+
+    // drill down from the current level
+    fn inspect_push(&mut self, env: &Environment, key: Option<String>, repr: &String) {
+        self.inspect_path.push((key.clone(), repr.to_string()));
+        self.inspect_forward.clear();
+        self.load_inspect(env, key, repr)
+    }
+
+    // reload the level at the end of the trail
+    fn inspect_current(&mut self, env: &Environment) {
+        if let Some((key, repr)) = self.inspect_path.last().cloned() {
+            self.load_inspect(env, key, &repr)
+        }
+    }
+
+    // evaluate the expression field and inspect the result
+    fn eval_expression(&mut self, env: &Environment) {
+        let core = env.core.as_ref().unwrap();
+        let expr = self.expression.trim().to_string();
+
+        if expr.is_empty() {
+            return;
+        }
+
+        match core.repr(&expr) {
+            Ok(repr) => {
+                self.expression_error = None;
+                self.inspect_root(env, Some(expr), &repr)
+            }
+            Err(ex) => self.expression_error = Some(core.system.error(ex)),
+        }
+    }
+
+    fn clear_inspect(&mut self) {
+        self.inspect_forward.clear();
+        self.inspect_path.clear();
+        self.inspect_tag_key = None;
+        self.inspect_tag_repr = None;
+        self.inspect_tree = None;
+    }
+
+    // forget the selected symbol and its filter, they belong to the old namespace
+    fn clear_symbol(&mut self) {
+        self.symbol_filter.clear();
+        self.symbol = None;
+        self.symbol_info = None;
+        self.symbol_value = None;
+        self.symbol_edit.clear();
+        self.symbol_edit_error = None;
+        self.symbol_changed = None;
+        self.symbol_tag_key = None;
+        self.symbol_tag_keys = None;
+        self.symbol_tag_repr = None;
+        self.symbol_tag_reprs = None;
+    }
+
     pub fn update(&mut self, env: &Environment, message: InspectorMessage) {
         match message {
             InspectorMessage::SelectNamespace(str) => {
+                self.filter = Filter::Namespaces;
                 self.namespace = Some(str.clone());
                 self.namespace_symbols = Some(Self::fetch_symbols_list(env, str.to_string()));
-                self.inspect_tag_key = None;
-                self.inspect_tag_keys = None;
-                self.inspect_tag_repr = None;
-                self.inspect_tag_reprs = None;
-                self.symbol = None;
-                self.symbol_info = None;
-                self.symbol_tag_key = None;
-                self.symbol_tag_keys = None;
-                self.symbol_tag_repr = None;
-                self.symbol_tag_reprs = None;
+                self.clear_inspect();
+                self.clear_symbol();
+                self.filter_lists()
             }
             InspectorMessage::SelectSymbol(name) => {
+                self.filter = Filter::Symbols;
                 self.symbol = Some(name);
 
-                self.symbol_info = Some(Self::inspect_symbol(
+                self.symbol_value = Self::fetch_symbol_value(
                     env,
                     self.namespace.as_ref().unwrap(),
                     self.symbol.as_ref().unwrap(),
-                ));
-
-                self.symbol_tag_keys = Some(
-                    Self::trimr(&Self::inspect_symbol_tag_keys(
-                        env,
-                        self.namespace.as_ref().unwrap(),
-                        self.symbol.as_ref().unwrap(),
-                    ))
-                    .to_string()
-                    .split(';')
-                    .map(|str| str.to_string())
-                    .collect::<Vec<_>>(),
                 );
+                self.symbol_edit = Self::edit_form(&self.symbol_value);
+                self.symbol_edit_error = None;
+                self.symbol_changed = None;
 
-                self.symbol_tag_reprs = Some(
-                    Self::trimr(&Self::inspect_symbol_tag_reprs(
-                        env,
-                        self.namespace.as_ref().unwrap(),
-                        self.symbol.as_ref().unwrap(),
-                    ))
-                    .to_string()
-                    .split(';')
-                    .map(|str| str.to_string())
-                    .collect::<Vec<_>>(),
-                );
+                self.load_symbol(env)
             }
             InspectorMessage::SelectSymbolTagKey(str) => {
                 let key_offset = self
@@ -240,77 +670,121 @@ impl InspectorTab {
                 self.symbol_tag_key = Some(str.clone());
                 self.symbol_tag_repr = Some(tag_repr.to_string());
 
-                self.inspect_tag_key = Some(str.clone());
-                self.inspect_tag_repr = Some(tag_repr.to_string());
-
-                self.inspect_tag_keys = Some(
-                    Self::inspect_repr_tag_keys(env, tag_repr)
-                        .split(';')
-                        .map(|str| str.to_string())
-                        .collect::<Vec<_>>(),
-                );
-
-                self.inspect_tag_reprs = Some(
-                    Self::inspect_repr_tag_reprs(env, tag_repr)
-                        .split(';')
-                        .map(|str| str.to_string())
-                        .collect::<Vec<_>>(),
-                );
+                self.inspect_root(env, Some(str), tag_repr);
             }
-            InspectorMessage::SelectInspectTagKey(str) => {
-                let key_offset = self
-                    .inspect_tag_keys
-                    .as_ref()
-                    .unwrap()
-                    .iter()
-                    .position(|list_str| *list_str == str)
-                    .unwrap();
-
-                let tag_repr = &self.inspect_tag_reprs.as_ref().unwrap()[key_offset].clone();
+            InspectorMessage::NamespaceFilter(pattern) => {
+                self.filter = Filter::Namespaces;
+                self.namespace_filter = pattern;
+                self.filter_lists()
+            }
+            InspectorMessage::SymbolFilter(pattern) => {
+                self.filter = Filter::Symbols;
+                self.symbol_filter = pattern;
+                self.filter_lists()
+            }
+            InspectorMessage::EventOccurred(event) => match event {
+                Event::Keyboard(KeyPressed {
+                    key_code: KeyCode::Down,
+                    ..
+                }) => self.step(env, true),
+                Event::Keyboard(KeyPressed {
+                    key_code: KeyCode::Up,
+                    ..
+                }) => self.step(env, false),
+                _ => (),
+            },
+            InspectorMessage::Inspect(repr) => self.inspect_root(env, None, &repr),
+            InspectorMessage::ToggleNode(path) => self.toggle_node(env, &path),
+            InspectorMessage::MoreNodes(path) => {
+                if let Some(node) = self.node_mut(&path) {
+                    Self::load_children(env, node)
+                }
+            }
+            InspectorMessage::InspectNode(path) => {
+                if let Some(node) = self.node_mut(&path).filter(|_| !path.is_empty()) {
+                    let (key, repr) = (node.key.to_string(), node.repr.to_string());
 
-                self.inspect_tag_key = Some(str.clone());
-                self.inspect_tag_repr = Some(tag_repr.to_string());
+                    self.inspect_push(env, Some(key), &repr)
+                }
+            }
+            InspectorMessage::Expression(expr) => self.expression = expr,
+            // evaluating may have changed anything we've cached
+            InspectorMessage::EvalExpression => {
+                self.eval_expression(env);
+                self.refresh(env)
+            }
+            InspectorMessage::SymbolEdit(form) => self.symbol_edit = form,
+            InspectorMessage::SetSymbol => {
+                self.set_symbol(env);
+                self.refresh(env)
+            }
+            InspectorMessage::InspectBack => {
+                if self.inspect_path.len() > 1 {
+                    let level = self.inspect_path.pop().unwrap();
 
-                self.inspect_tag_keys = Some(
-                    Self::inspect_repr_tag_keys(env, tag_repr)
-                        .split(';')
-                        .map(|str| str.to_string())
-                        .collect::<Vec<_>>(),
-                );
+                    self.inspect_forward.push(level);
+                    self.inspect_current(env)
+                }
+            }
+            InspectorMessage::InspectForward => {
+                if let Some(level) = self.inspect_forward.pop() {
+                    self.inspect_path.push(level);
+                    self.inspect_current(env)
+                }
+            }
+            InspectorMessage::InspectCrumb(nth) => {
+                while self.inspect_path.len() > nth + 1 {
+                    let level = self.inspect_path.pop().unwrap();
 
-                self.inspect_tag_reprs = Some(
-                    Self::inspect_repr_tag_reprs(env, tag_repr)
-                        .split(';')
-                        .map(|str| str.to_string())
-                        .collect::<Vec<_>>(),
-                );
+                    self.inspect_forward.push(level)
+                }
+                self.inspect_current(env)
+            }
+            InspectorMessage::Refresh => self.refresh(env),
+            InspectorMessage::Tick(now) => {
+                let stale = match self.refreshed {
+                    Some(at) => now.saturating_duration_since(at) >= Self::REFRESH,
+                    None => true,
+                };
+
+                if stale {
+                    self.refresh(env)
+                }
             }
-            InspectorMessage::Refresh => match self.namespaces {
-                Some(_) => (),
-                None => self.namespaces = Some(Self::fetch_ns_list(env)),
-            },
             InspectorMessage::Clear => {
                 self.namespace = None;
                 self.namespace_symbols = None;
-                self.inspect_tag_key = None;
-                self.inspect_tag_keys = None;
-                self.inspect_tag_repr = None;
-                self.inspect_tag_reprs = None;
-                self.symbol = None;
-                self.symbol_info = None;
-                self.symbol_tag_key = None;
-                self.symbol_tag_keys = None;
-                self.symbol_tag_repr = None;
-                self.symbol_tag_reprs = None;
+                self.clear_inspect();
+                self.clear_symbol();
+                self.filter_lists()
             }
         }
     }
 
+    // filter input and how many of the list it matches
+    fn filter_box<'a>(
+        placeholder: &str,
+        pattern: &str,
+        matches: usize,
+        total: usize,
+        on_change: fn(String) -> InspectorMessage,
+    ) -> Element<'a, InspectorMessage> {
+        row![
+            text_input(placeholder, pattern, on_change)
+                .size(13)
+                .width(110),
+            text(format!("{}/{}", matches, total)).size(13),
+        ]
+        .spacing(4)
+        .align_items(Alignment::Center)
+        .into()
+    }
+
     fn namespaces(&self, width: i32, height: i32) -> Element<InspectorMessage> {
         let column = match &self.namespaces {
             Some(symvec) => {
                 let selection_list = SelectionList::new_with(
-                    symvec,
+                    &self.namespace_matches,
                     InspectorMessage::SelectNamespace,
                     18.0,
                     1.0,
@@ -323,6 +797,13 @@ impl InspectorTab {
                     text("namespaces:".to_string()).size(20),
                     horizontal_rule(1),
                     Space::new(width as u16, 5),
+                    Self::filter_box(
+                        "filter",
+                        &self.namespace_filter,
+                        self.namespace_matches.len(),
+                        symvec.len(),
+                        InspectorMessage::NamespaceFilter,
+                    ),
                     selection_list,
                 )
             }
@@ -347,16 +828,11 @@ impl InspectorTab {
             .into()
     }
 
-    fn namespace_symbols(
-        &self,
-        _: &Environment,
-        width: i32,
-        height: i32,
-    ) -> Element<InspectorMessage> {
+    fn namespace_symbols(&self, width: i32, height: i32) -> Element<InspectorMessage> {
         let column = match &self.namespace_symbols {
             Some(sym_vec) => {
                 let selection_list = SelectionList::new_with(
-                    sym_vec,
+                    &self.symbol_matches,
                     InspectorMessage::SelectSymbol,
                     18.0,
                     1.0,
@@ -369,6 +845,13 @@ impl InspectorTab {
                     text(self.namespace.as_ref().unwrap()).size(20),
                     horizontal_rule(1),
                     Space::new(width as u16, 5),
+                    Self::filter_box(
+                        "filter",
+                        &self.symbol_filter,
+                        self.symbol_matches.len(),
+                        sym_vec.len(),
+                        InspectorMessage::SymbolFilter,
+                    ),
                     selection_list,
                 )
             }
@@ -393,7 +876,7 @@ impl InspectorTab {
             .into()
     }
 
-    fn symbol(&self, _: &Environment, width: i32, height: i32) -> Element<InspectorMessage> {
+    fn symbol(&self, width: i32, height: i32) -> Element<InspectorMessage> {
         match &self.symbol_info {
             Some(descr) => {
                 let selection_list = SelectionList::new_with(
@@ -453,25 +936,72 @@ impl InspectorTab {
         }
     }
 
-    fn inspect(&self, env: &Environment, width: i32, height: i32) -> Element<InspectorMessage> {
-        let column = match &self.inspect_tag_repr {
-            Some(repr) => {
-                let selection_list = SelectionList::new_with(
-                    self.inspect_tag_keys.as_ref().unwrap(),
-                    InspectorMessage::SelectInspectTagKey,
-                    18.0,
-                    1.0,
-                    SelectionListStyles::Default,
-                )
-                .width(Length::Fixed(150.0))
-                .height(Length::Fixed(150.0));
+    // one row per node, indented by depth, expanded children below it
+    fn tree_rows<'a>(
+        node: &'a Node,
+        path: Vec<usize>,
+        rows: Column<'a, InspectorMessage>,
+    ) -> Column<'a, InspectorMessage> {
+        let depth = path.len();
+        let indent = Space::new((depth * 12) as u16, 0);
+
+        let toggle: Element<_> = if node.is_expandable() && depth < Self::TREE_DEPTH {
+            button(text(if node.expanded { "-" } else { "+" }).size(13))
+                .padding(0)
+                .width(16)
+                .style(theme::Button::Text)
+                .on_press(InspectorMessage::ToggleNode(path.clone()))
+                .into()
+        } else {
+            Space::new(16, 0).into()
+        };
+
+        let value = text(format!("{}: {}", node.key, Self::elide(&node.value))).size(13);
+        let label = button(if Self::is_recent(node.changed) {
+            value.style(Color::from_rgb8(0xe0, 0x60, 0x00))
+        } else {
+            value
+        })
+        .padding(0)
+        .style(theme::Button::Text)
+        .on_press(InspectorMessage::InspectNode(path.clone()));
+
+        let mut rows = rows.push(row![indent, toggle, label].align_items(Alignment::Center));
+
+        if node.expanded {
+            for (nth, child) in node.children.iter().enumerate() {
+                let mut child_path = path.clone();
+
+                child_path.push(nth);
+                rows = Self::tree_rows(child, child_path, rows)
+            }
+
+            if node.unloaded() > 0 {
+                rows = rows.push(row![
+                    Space::new(((depth + 1) * 12 + 16) as u16, 0),
+                    button(text(format!("show more ({})", node.unloaded())).size(13))
+                        .padding(0)
+                        .style(theme::Button::Text)
+                        .on_press(InspectorMessage::MoreNodes(path)),
+                ])
+            }
+        }
+
+        rows
+    }
+
+    // views only read what update() fetched from the core
+    fn inspect(&self, width: i32, height: i32) -> Element<InspectorMessage> {
+        let column = match &self.inspect_tree {
+            Some(tree) => {
+                let rows = Self::tree_rows(tree, Vec::new(), Column::new().spacing(1));
 
                 column!(
                     text("inspect:".to_string()).size(20),
                     horizontal_rule(1),
                     Space::new(width as u16, 5),
-                    text(Self::inspect_repr(env, repr)).size(16),
-                    selection_list,
+                    text(&tree.summary).size(16),
+                    scrollable(rows).height(Length::Fill),
                 )
             }
             None => {
@@ -495,7 +1025,95 @@ impl InspectorTab {
             .into()
     }
 
-    pub fn view(&self, env: &Environment) -> Element<'_, Message, Renderer> {
+    // set the selected symbol from a form
+    fn symbol_editor(&self) -> Element<InspectorMessage> {
+        let (ns, symbol) = match (&self.namespace, &self.symbol) {
+            (Some(ns), Some(symbol)) => (ns, symbol),
+            _ => return Space::new(Length::Fill, 0).into(),
+        };
+
+        let name = text(format!("{}:{}", Self::triml(ns), symbol)).size(13);
+        let editor = row![
+            if Self::is_recent(self.symbol_changed) {
+                name.style(Color::from_rgb8(0xe0, 0x60, 0x00))
+            } else {
+                name
+            },
+            text_input(
+                "value form",
+                &self.symbol_edit,
+                InspectorMessage::SymbolEdit
+            )
+            .on_submit(InspectorMessage::SetSymbol)
+            .size(13)
+            .width(300),
+            button(text("set".to_string()).size(13))
+                .height(28)
+                .style(theme::Button::Primary)
+                .on_press(InspectorMessage::SetSymbol),
+        ]
+        .spacing(8)
+        .align_items(Alignment::Center);
+
+        match &self.symbol_edit_error {
+            Some(error) => column![
+                editor,
+                text(error)
+                    .size(13)
+                    .style(Color::from_rgb8(0xc0, 0x20, 0x20)),
+            ]
+            .spacing(4)
+            .into(),
+            None => editor.into(),
+        }
+    }
+
+    // back and forward, then the tag keys from the root to the current level
+    fn breadcrumbs(&self) -> Element<InspectorMessage> {
+        let back = button(text("<".to_string()).size(13)).height(24);
+        let forward = button(text(">".to_string()).size(13)).height(24);
+
+        let mut crumbs = Row::new()
+            .align_items(Alignment::Center)
+            .spacing(4)
+            .push(if self.inspect_path.len() > 1 {
+                back.on_press(InspectorMessage::InspectBack)
+            } else {
+                back
+            })
+            .push(if self.inspect_forward.is_empty() {
+                forward
+            } else {
+                forward.on_press(InspectorMessage::InspectForward)
+            });
+
+        let last = self.inspect_path.len().saturating_sub(1);
+        for (nth, (key, _)) in self.inspect_path.iter().enumerate() {
+            let label = match key {
+                Some(key) => key.to_string(),
+                None => "object".to_string(),
+            };
+
+            if nth > 0 {
+                crumbs = crumbs.push(text("/".to_string()).size(13))
+            }
+
+            crumbs = crumbs.push(
+                button(text(label).size(13))
+                    .height(24)
+                    .style(if nth == last {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    })
+                    .on_press(InspectorMessage::InspectCrumb(nth)),
+            )
+        }
+
+        crumbs.into()
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
         let controls = row![
             button(text("clear".to_string()).size(13))
                 .height(28)
@@ -504,9 +1122,26 @@ impl InspectorTab {
             button(text("refresh".to_string()).size(13))
                 .height(28)
                 .style(theme::Button::Primary)
-                .on_press(InspectorMessage::Refresh)
+                .on_press(InspectorMessage::Refresh),
+            text_input("expression", &self.expression, InspectorMessage::Expression)
+                .on_submit(InspectorMessage::EvalExpression)
+                .size(13)
+                .width(300),
+            button(text("inspect".to_string()).size(13))
+                .height(28)
+                .style(theme::Button::Primary)
+                .on_press(InspectorMessage::EvalExpression),
         ]
-        .spacing(8);
+        .spacing(8)
+        .align_items(Alignment::Center);
+
+        let error: Element<'_, InspectorMessage> = match &self.expression_error {
+            Some(error) => text(error)
+                .size(13)
+                .style(Color::from_rgb8(0xc0, 0x20, 0x20))
+                .into(),
+            None => Space::new(Length::Fill, 0).into(),
+        };
 
         let content: Element<'_, InspectorMessage> = Container::new(
             Column::new()
@@ -514,16 +1149,20 @@ impl InspectorTab {
                 .max_width(800)
                 .padding(20)
                 .push(controls.width(Length::Fill))
+                .push(error)
+                .push(Space::new(Length::Fill, 8))
+                .push(self.breadcrumbs())
                 .push(
                     Row::new()
                         .align_items(Alignment::Start)
                         .height(175)
                         .padding(20)
                         .push(self.namespaces(200, 175))
-                        .push(self.namespace_symbols(env, 200, 175))
-                        .push(self.symbol(env, 200, 175))
-                        .push(self.inspect(env, 200, 175)),
-                ),
+                        .push(self.namespace_symbols(200, 175))
+                        .push(self.symbol(200, 175))
+                        .push(self.inspect(200, 175)),
+                )
+                .push(self.symbol_editor()),
         )
         .align_x(Horizontal::Left)
         .align_y(Vertical::Top)
@@ -10,64 +10,671 @@
 use {
     super::{
         super::{
-            super::{Core, Environment},
+            super::{config, Core, Environment},
             window::{Message, Tab},
         },
+        completion::Completer,
+        editor::LineEditor,
         eth::Eth,
-        tty::{Tty, TtyBuilder},
+        history::History,
+        syntax::{self, Class},
+        transcript::{Entry, Transcript},
+        tty::{Cursor, Span, Tty, TtyBuilder},
     },
     iced::{
         alignment::{Horizontal, Vertical},
         executor,
-        keyboard::Event::CharacterReceived,
-        subscription,
-        widget::{container, horizontal_rule, text, Column, Container, Image, Slider, Text},
-        window, Alignment, Application, Command, Element, Event, Length, Renderer, Subscription,
-        Theme,
+        keyboard::{
+            Event::{CharacterReceived, KeyPressed},
+            KeyCode,
+        },
+        mouse, subscription, theme,
+        widget::{
+            button, container, horizontal_rule, text, text_input, Column, Container, Image, Row,
+            Slider, Text,
+        },
+        window, Alignment, Application, Color, Command, Element, Event, Length, Renderer,
+        Subscription, Theme,
     },
     iced_aw::tab_bar::TabLabel,
     mu::{Condition, Exception, Mu, Result, System, Tag},
+    std::collections::VecDeque,
 };
 
 #[derive(Debug, Clone)]
 pub enum ListenerMessage {
     EventOccurred(Event),
+    Complete(String),
+    Paste(Option<String>),
+    TranscriptPath(String),
+    Dribble,
+    SaveTranscript,
+}
+
+// reverse incremental history search
+struct Search {
+    pattern: String,
+    found: Option<usize>,
+}
+
+// scrollback search, lines are counted from the first line ever written
+struct Find {
+    pattern: String,
+    lines: Vec<usize>,
+    nth: Option<usize>,
 }
 
 pub struct ListenerTab {
     command: String,
+    completer: Completer,
+    completions: Vec<String>,
+    core: Option<Core>,
+    editor: LineEditor,
+    evaluations: usize,
+    find: Option<Find>,
+    history: History,
+    inputs: Vec<String>,
+    // messages for the about console
+    log: Vec<String>,
+    name: String,
+    ns: Option<String>,
+    search: Option<Search>,
+    stash: String,
+    prompt: String,
+    prompt_format: String,
+    results: Vec<String>,
+    transcript: Transcript,
+    transcript_path: String,
+    transcript_status: String,
     tty: Tty,
+    // reprs behind the value links, link n is values[n - values_dropped]
+    values: VecDeque<String>,
+    values_dropped: usize,
 }
 
 type CoreResult<T> = std::result::Result<T, Exception>;
 
 impl ListenerTab {
-    pub fn new() -> Self {
-        let tty = TtyBuilder::new().rows(19).cursor('_').build();
+    const COMPLETIONS: usize = 12;
+    const DEFAULT_NS: &'static str = "core";
+    const VALUES: usize = 1024;
+    const WIDTH: u16 = 800;
+
+    // sessions with their own core don't share state with the rest of the ui
+    pub fn new(env: &Environment, name: &str, core: Option<Core>) -> Self {
+        let (_, conf) = &env.config;
+        let tty = TtyBuilder::new()
+            .rows(19)
+            .scrollback(config::TextUi::scrollback(conf))
+            .timestamps(config::TextUi::timestamps(conf))
+            .cursor(Cursor::Underline)
+            .build();
+        let prompt_format = config::Listener::prompt(conf);
+        let prompt = Self::ns_prompt(&prompt_format, &None);
 
-        tty.write_string("eth> ".to_string());
+        let history = History::load(
+            &env.config_path.join(config::Listener::history_file(conf)),
+            config::Listener::history_size(conf),
+        );
+
+        tty.write_string(prompt.clone());
 
         ListenerTab {
             command: String::new(),
+            completer: Completer::new(),
+            completions: Vec::new(),
+            core,
+            editor: LineEditor::new(),
+            evaluations: 0,
+            find: None,
+            history,
+            inputs: Vec::new(),
+            log: Vec::new(),
+            name: name.to_string(),
+            ns: None,
+            search: None,
+            stash: String::new(),
+            prompt,
+            prompt_format,
+            results: Vec::new(),
+            transcript: Transcript::new(),
+            transcript_path: String::new(),
+            transcript_status: String::new(),
             tty,
+            values: VecDeque::new(),
+            values_dropped: 0,
+        }
+    }
+
+    fn core<'a>(core: &'a Option<Core>, env: &'a Environment) -> &'a Core {
+        match core {
+            Some(core) => core,
+            None => env.core.as_ref().unwrap(),
+        }
+    }
+
+    pub fn is_shared(&self) -> bool {
+        self.core.is_none()
+    }
+
+    // forms evaluated so far, so the ui can tell when state may have changed
+    pub fn evaluations(&self) -> usize {
+        self.evaluations
+    }
+
+    pub fn take_log(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.log)
+    }
+
+    // the repr behind a value link, the oldest links are forgotten
+    pub fn value(&self, nth: usize) -> Option<String> {
+        self.values
+            .get(nth.checked_sub(self.values_dropped)?)
+            .cloned()
+    }
+
+    fn write_value(&mut self, string: &str, repr: String) {
+        self.tty
+            .write_span(Span::link(string, self.values_dropped + self.values.len()));
+        self.values.push_back(repr);
+
+        if self.values.len() > Self::VALUES {
+            self.values.pop_front();
+            self.values_dropped += 1
+        }
+    }
+
+    fn ns_prompt(format: &str, ns: &Option<String>) -> String {
+        format.replace("{ns}", ns.as_deref().unwrap_or(Self::DEFAULT_NS))
+    }
+
+    // (in-ns :ns) switches namespace, (in-ns) goes back to the default
+    fn parse_in_ns(command: &str) -> Option<Option<String>> {
+        let args = command.trim().strip_prefix("(in-ns")?.strip_suffix(')')?;
+
+        if !args.is_empty() && !args.starts_with(char::is_whitespace) {
+            return None;
+        }
+
+        match args.split_whitespace().collect::<Vec<_>>()[..] {
+            [] => Some(None),
+            [ns] => Some(Some(ns.trim_start_matches(':').to_string())),
+            _ => None,
+        }
+    }
+
+    fn in_ns(&mut self, env: &Environment, ns: Option<String>) -> String {
+        let core = Self::core(&self.core, env);
+
+        match ns {
+            Some(ns) if !self.completer.namespaces(core).contains(&ns) => {
+                format!("in-ns: no namespace :{}", ns)
+            }
+            _ => {
+                self.ns = ns;
+                format!(":{}", self.ns.as_deref().unwrap_or(Self::DEFAULT_NS))
+            }
+        }
+    }
+
+    // the repr of the form command reads as, in a namespace other than the
+    // default the reader resolves unqualified symbols there
+    fn read(&self, env: &Environment, command: &str) -> CoreResult<String> {
+        Self::core(&self.core, env).read_repr(self.ns.as_deref(), command)
+    }
+
+    // continuation lines get a prompt of the same width
+    fn prompt(&self) -> String {
+        if self.command.is_empty() {
+            self.prompt.clone()
+        } else {
+            let width = self.prompt.chars().count();
+
+            format!("{}> ", ".".repeat(width.max(2) - 2))
+        }
+    }
+
+    fn class_color(class: Class) -> Option<Color> {
+        match class {
+            Class::Char | Class::String => Some(Color::from_rgb8(0x2e, 0x7d, 0x32)),
+            Class::Comment => Some(Color::from_rgb8(0x80, 0x80, 0x80)),
+            Class::Keyword => Some(Color::from_rgb8(0x1f, 0x5f, 0xbf)),
+            Class::Number => Some(Color::from_rgb8(0xb0, 0x5a, 0x00)),
+            Class::Special => Some(Color::from_rgb8(0xa0, 0x30, 0xa0)),
+            _ => None,
+        }
+    }
+
+    // color the input line in the context of any continued lines. parens
+    // without a partner are flagged, extra closes and opens not yet closed
+    fn highlight(&self, line: &str) -> Vec<Span> {
+        let (text, offset) = if self.command.is_empty() {
+            (line.to_string(), 0)
+        } else {
+            (
+                format!("{}\n{}", self.command, line),
+                self.command.chars().count() + 1,
+            )
+        };
+
+        let chars = text.chars().collect::<Vec<_>>();
+        let tokens = syntax::tokenize(&text);
+        let mut colors = vec![None; chars.len()];
+
+        for token in &tokens {
+            for color in &mut colors[token.start..token.end] {
+                *color = Self::class_color(token.class)
+            }
+        }
+
+        for paren in syntax::unmatched_parens(&tokens, &chars) {
+            colors[paren] = Some(Color::from_rgb8(0xd0, 0x00, 0x00))
+        }
+
+        let cursor = offset + self.editor.cursor();
+        let at_cursor = [cursor, cursor.wrapping_sub(1)]
+            .into_iter()
+            .find(|at| *at < chars.len() && (chars[*at] == '(' || chars[*at] == ')'));
+
+        if let Some(at) = at_cursor {
+            if let Some(partner) = syntax::matching_paren(&tokens, &chars, at) {
+                for paren in [at, partner] {
+                    colors[paren] = Some(Color::from_rgb8(0x00, 0x99, 0xcc))
+                }
+            }
+        }
+
+        let mut spans: Vec<Span> = Vec::new();
+        for (ch, color) in chars.iter().zip(colors).skip(offset) {
+            match spans.last_mut() {
+                Some(span) if span.color == color => span.text.push(*ch),
+                _ => spans.push(Span::new(&ch.to_string(), color)),
+            }
+        }
+
+        spans
+    }
+
+    // redraw the input line
+    fn echo(&self) {
+        match &self.search {
+            Some(search) => {
+                let line = match search.found {
+                    Some(nth) => format!(
+                        "(reverse-i-search)'{}': {}",
+                        search.pattern,
+                        self.history.get(nth).unwrap()
+                    ),
+                    None if search.pattern.is_empty() => "(reverse-i-search)'': ".to_string(),
+                    None => format!("(failing reverse-i-search)'{}': no match", search.pattern),
+                };
+                let column = line.chars().count();
+
+                self.tty.write_input(line, column)
+            }
+            None => {
+                let prompt = self.prompt();
+                let mut spans = vec![Span::plain(&prompt)];
+
+                spans.extend(self.highlight(&self.editor.text()));
+                self.tty
+                    .write_input_spans(spans, prompt.chars().count() + self.editor.cursor())
+            }
+        }
+    }
+
+    // complete the symbol before the cursor, several candidates open the popup
+    fn complete(&mut self, env: &Environment) {
+        let core = Self::core(&self.core, env);
+        let prefix = self.editor.prefix();
+
+        let ns = self.ns.as_deref().unwrap_or(Self::DEFAULT_NS);
+        let candidates = self.completer.complete(core, ns, &prefix);
+        match candidates.len() {
+            0 => (),
+            1 => self.editor.replace_prefix(&candidates[0]),
+            _ => {
+                let common = Completer::common_prefix(&candidates);
+
+                if common.chars().count() > prefix.chars().count() {
+                    self.editor.replace_prefix(&common)
+                }
+
+                self.completions = candidates
+            }
         }
     }
 
-    pub fn eval(&self, env: &Environment, expr: &String) -> CoreResult<String> {
-        let mu = env.core.as_ref().unwrap().system.mu();
-        let rstream = env.core.as_ref().unwrap().eval_stream;
+    pub fn select(&self, line: usize, column: usize, extend: bool) {
+        self.tty.select(line, column, extend)
+    }
+
+    pub fn drag(&self, line: usize, column: usize) {
+        self.tty.drag(line, column)
+    }
+
+    pub fn selection(&self) -> Option<String> {
+        self.tty.selection()
+    }
+
+    pub fn resize(&self, columns: usize) {
+        self.tty.resize(columns)
+    }
+
+    // the command so far and the line being edited
+    fn input(&self) -> String {
+        if self.command.is_empty() {
+            self.editor.text()
+        } else {
+            format!("{}\n{}", self.command, self.editor.text())
+        }
+    }
+
+    // pasted text is one input, inserted as it is. its lines but the last
+    // become continuation lines without indentation, and a trailing newline
+    // enters the whole input
+    fn paste(&mut self, env: &Environment, text: &str) {
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        let (text, enter) = match text.strip_suffix('\n') {
+            Some(text) => (text, true),
+            None => (text.as_str(), false),
+        };
+        let mut lines = text.split('\n');
+        let tail = self.editor.kill();
+
+        self.tty.scroll_to_bottom();
+        self.editor.insert_str(lines.next().unwrap_or_default());
+        for line in lines {
+            self.echo();
+            self.tty.scroll();
+            self.command = self.input();
+            self.editor.set(line)
+        }
+
+        // the rest of the line we pasted into follows the paste
+        self.editor.insert_str(&tail);
+        for _ in tail.chars() {
+            self.editor.left()
+        }
+
+        self.echo();
+        if enter {
+            self.enter(env)
+        }
+    }
+
+    // forget matches the scrollback has dropped
+    fn prune_find(&mut self) {
+        let dropped = self.tty.dropped();
+
+        if let Some(find) = &mut self.find {
+            let gone = find
+                .lines
+                .iter()
+                .take_while(|line| **line < dropped)
+                .count();
+
+            find.lines.drain(..gone);
+            find.nth = find.nth.and_then(|nth| nth.checked_sub(gone))
+        }
+    }
+
+    // move to an older or newer matching line
+    fn find_next(&mut self, older: bool) {
+        self.prune_find();
+
+        let dropped = self.tty.dropped();
+        if let Some(find) = &mut self.find {
+            let len = find.lines.len();
+
+            find.nth = match find.nth {
+                _ if len == 0 => None,
+                None => Some(len - 1),
+                Some(nth) if older => Some(nth.saturating_sub(1)),
+                Some(nth) => Some((nth + 1).min(len - 1)),
+            };
+
+            if let Some(nth) = find.nth {
+                self.tty.show_line(find.lines[nth] - dropped)
+            }
+        }
+    }
+
+    fn find_pattern(&mut self, pattern: String) {
+        let dropped = self.tty.dropped();
+        let lines = self
+            .tty
+            .find(&pattern)
+            .into_iter()
+            .map(|line| line + dropped)
+            .collect();
+
+        self.tty.set_highlight(Some(pattern.clone()));
+        self.find = Some(Find {
+            pattern,
+            lines,
+            nth: None,
+        });
+        self.find_next(true)
+    }
+
+    fn close_find(&mut self) {
+        self.find = None;
+        self.tty.set_highlight(None);
+        self.tty.scroll_to_bottom()
+    }
 
-        match mu.read_string(expr.to_string()) {
-            Ok(form) => match mu.compile(form) {
-                Ok(form) => match mu.eval(form) {
-                    Ok(value) => match mu.write(value, false, rstream) {
-                        Ok(_) => match mu.get_string(rstream) {
-                            Ok(string) => {
-                                Eth::run(env);
-                                Ok(string)
+    fn find_event(&mut self, key_event: &iced::keyboard::Event) {
+        let pattern = match &self.find {
+            Some(find) => find.pattern.clone(),
+            None => return,
+        };
+
+        match key_event {
+            KeyPressed { key_code, .. } => match key_code {
+                KeyCode::Escape => self.close_find(),
+                KeyCode::Up => self.find_next(true),
+                KeyCode::Down => self.find_next(false),
+                KeyCode::PageUp => self.tty.page_up(),
+                KeyCode::PageDown => self.tty.page_down(),
+                _ => (),
+            },
+            CharacterReceived(ch) => match ch {
+                '\u{6}' | '\r' | '\n' => self.find_next(true),
+                '\u{7}' => self.close_find(),
+                '\u{8}' => {
+                    let mut pattern = pattern;
+
+                    pattern.pop();
+                    self.find_pattern(pattern)
+                }
+                _ if ch.is_control() => (),
+                _ => self.find_pattern(format!("{}{}", pattern, ch)),
+            },
+            _ => (),
+        }
+    }
+
+    // search from just before the current match
+    fn search_history(&mut self, older: bool) {
+        let history = &self.history;
+
+        if let Some(search) = &mut self.search {
+            let before = match search.found {
+                Some(nth) if older => nth,
+                Some(nth) => nth + 1,
+                None => history.len(),
+            };
+
+            // a miss drops the old match, the next ^R starts over from the newest
+            search.found = history.search(&search.pattern, before)
+        }
+    }
+
+    fn accept_search(&mut self) {
+        if let Some(search) = self.search.take() {
+            if let Some(nth) = search.found {
+                self.editor.set(self.history.get(nth).unwrap())
+            }
+        }
+    }
+
+    // returns true if the search consumed the event
+    fn search_event(&mut self, key_event: &iced::keyboard::Event) -> bool {
+        match key_event {
+            KeyPressed { key_code, .. } => match key_code {
+                KeyCode::Escape => {
+                    self.search = None;
+                    true
+                }
+                KeyCode::Left
+                | KeyCode::Right
+                | KeyCode::Up
+                | KeyCode::Down
+                | KeyCode::Home
+                | KeyCode::End
+                | KeyCode::Delete => {
+                    self.accept_search();
+                    false
+                }
+                _ => true,
+            },
+            CharacterReceived(ch) => match ch {
+                '\u{12}' => {
+                    self.search_history(true);
+                    true
+                }
+                '\u{7}' => {
+                    self.search = None;
+                    true
+                }
+                '\r' | '\n' => {
+                    self.accept_search();
+                    true
+                }
+                '\u{8}' => {
+                    if let Some(search) = &mut self.search {
+                        search.pattern.pop();
+                        search.found = None;
+                    }
+                    self.search_history(true);
+                    true
+                }
+                _ if ch.is_control() => {
+                    self.accept_search();
+                    false
+                }
+                _ => {
+                    if let Some(search) = &mut self.search {
+                        search.pattern.push(*ch);
+                    }
+                    self.search_history(false);
+                    true
+                }
+            },
+            _ => true,
+        }
+    }
+
+    // submit the input line, evaluating the form if it's complete
+    fn enter(&mut self, env: &Environment) {
+        self.tty.scroll_to_bottom();
+
+        let command = self.input();
+
+        self.tty.scroll();
+
+        let state = syntax::scan(&command);
+
+        // nothing to read, just prompt again
+        if state.is_complete()
+            && syntax::tokenize(&command)
+                .iter()
+                .all(|token| matches!(token.class, Class::Whitespace | Class::Comment))
+        {
+            self.command.clear();
+            self.editor.clear();
+            self.echo();
+            return;
+        }
+
+        if !state.is_complete() {
+            self.command = command;
+            self.editor.clear();
+            self.editor.insert_str(&" ".repeat(state.indent()));
+            self.echo();
+            return;
+        }
+
+        self.command.clear();
+        self.record(Entry::Input {
+            prompt: self.prompt.clone(),
+            form: command.clone(),
+        });
+        let output = match Self::parse_in_ns(&command) {
+            Some(ns) => {
+                let output = self.in_ns(env, ns);
+                self.tty.write_string(output.clone());
+                output
+            }
+            None => {
+                self.evaluations += 1;
+                match self.eval(env, &command) {
+                    Ok((value, string)) => {
+                        self.write_value(&string, value);
+                        string
+                    }
+                    Err(e) => self.error_panel(env, e, &command),
+                }
+            }
+        };
+        self.record(Entry::Output(output));
+        self.completer.invalidate();
+        self.tty.scroll();
+        self.prune_find();
+        if let Err(e) = self.history.push(&command) {
+            self.log.push(format!(
+                "listener: can't save history to {}, {}",
+                self.history.path().display(),
+                e
+            ))
+        }
+
+        self.prompt = Self::ns_prompt(&self.prompt_format, &self.ns);
+        self.editor.clear();
+        self.echo()
+    }
+
+    // the repr of the value and the printed value. like lisp's +, eth:+ is
+    // the form before this one, so the forms are shifted once this one has
+    // been evaluated, whether or not that worked
+    pub fn eval(&mut self, env: &Environment, expr: &String) -> CoreResult<(String, String)> {
+        let read = self.read(env, expr)?;
+        let value = self.eval_read(env, &read);
+
+        self.bind_inputs(env, read);
+        if let Ok((repr, _)) = &value {
+            self.bind_results(env, repr.clone())
+        }
+
+        value
+    }
+
+    fn eval_read(&self, env: &Environment, read: &str) -> CoreResult<(String, String)> {
+        let core = Self::core(&self.core, env);
+        let mu = core.system.mu();
+        let rstream = core.eval_stream;
+
+        match core.repr(&format!("(mu:eval (mu:compile (mu:repr :t {})))", read)) {
+            Ok(repr) => match core.from_repr(&repr) {
+                Ok(value) => match mu.write(value, false, rstream) {
+                    Ok(_) => match mu.get_string(rstream) {
+                        Ok(string) => {
+                            if self.is_shared() {
+                                Eth::run(env)
                             }
-                            Err(e) => Err(e),
-                        },
+                            Ok((repr, string))
+                        }
                         Err(e) => Err(e),
                     },
                     Err(e) => Err(e),
@@ -78,42 +685,280 @@ impl ListenerTab {
         }
     }
 
-    pub fn error(env: &Environment, ex: Exception) -> String {
-        let system = &env.core.as_ref().unwrap().system;
+    pub fn error(core: &Core, ex: Exception) -> String {
+        core.system.error(ex)
+    }
+
+    // eth:+, eth:++ and eth:+++ are the last forms
+    fn bind_inputs(&mut self, env: &Environment, repr: String) {
+        let core = Self::core(&self.core, env);
+
+        self.inputs.insert(0, repr);
+        self.inputs.truncate(3);
+        self.log
+            .extend(Self::bind_history(core, ["+", "++", "+++"], &self.inputs))
+    }
+
+    // eth:*, eth:** and eth:*** are the last results
+    fn bind_results(&mut self, env: &Environment, repr: String) {
+        let core = Self::core(&self.core, env);
 
-        system.error(ex)
+        self.results.insert(0, repr);
+        self.results.truncate(3);
+        self.log
+            .extend(Self::bind_history(core, ["*", "**", "***"], &self.results))
+    }
+
+    // bind the names to the reprs, newest first, and describe any failures
+    fn bind_history(core: &Core, names: [&str; 3], reprs: &[String]) -> Vec<String> {
+        if !core.init_loaded {
+            return Vec::new();
+        }
+
+        names
+            .iter()
+            .zip(reprs.iter())
+            .filter_map(|(name, repr)| match core.intern_repr(":eth", name, repr) {
+                Ok(_) => None,
+                Err(ex) => Some(format!(
+                    "listener: can't bind eth:{}, {}",
+                    name,
+                    core.system.error(ex)
+                )),
+            })
+            .collect()
+    }
+
+    // condition, object and the form that failed, each clickable through to
+    // the inspector. the object is re-read from its printed form, so it isn't
+    // a link when it doesn't print readably
+    fn error_panel(&mut self, env: &Environment, ex: Exception, form: &str) -> String {
+        let core = Self::core(&self.core, env);
+        let system = &core.system;
+        let condition = Core::condition_keyword(ex.condition).to_string();
+        let object = system.write(ex.object, true);
+        // continuation lines are joined to fit the field
+        let source = form.lines().map(str::trim).collect::<Vec<_>>().join(" ");
+        let parts = vec![
+            ("condition", core.repr(&condition).ok(), condition),
+            (
+                "object",
+                core.repr(&format!("(:quote {})", object)).ok(),
+                object,
+            ),
+            ("source", self.read(env, form).ok(), source),
+            (
+                "backtrace",
+                None,
+                "unavailable, mu doesn't record one".to_string(),
+            ),
+        ];
+        let summary = Self::error(core, ex);
+        let mut lines = vec![summary.clone()];
+
+        self.tty.write_span(Span::new(
+            &summary,
+            Some(Color::from_rgb8(0xc0, 0x20, 0x20)),
+        ));
+
+        for (label, value, string) in parts {
+            let label = format!("  {:<10} ", label);
+
+            self.tty.scroll();
+            self.tty.write_string(label.clone());
+            match value {
+                Some(repr) => self.write_value(&string, repr),
+                None => self.tty.write_string(string.clone()),
+            }
+            lines.push(format!("{}{}", label, string))
+        }
+
+        lines.join("\n")
+    }
+
+    // dribble failures stop the dribble rather than failing every entry
+    fn record(&mut self, entry: Entry) {
+        if let Err(e) = self.transcript.push(entry) {
+            self.transcript.stop_dribble();
+            self.transcript_status = format!("dribble: {}", e)
+        }
+    }
+
+    fn dribble(&mut self) {
+        if self.transcript.dribble_path().is_some() {
+            self.transcript.stop_dribble();
+            self.transcript_status = "dribble off".to_string();
+            return;
+        }
+
+        let path = std::path::Path::new(&self.transcript_path);
+        self.transcript_status = match self.transcript.dribble(path) {
+            Ok(()) => format!("dribbling to {}", path.display()),
+            Err(e) => format!("dribble: {}", e),
+        }
+    }
+
+    fn save_transcript(&mut self) {
+        let path = std::path::Path::new(&self.transcript_path);
+
+        self.transcript_status = match self.transcript.save(path) {
+            Ok(()) => format!("saved {}", path.display()),
+            Err(e) => format!("save: {}", e),
+        }
     }
 
     pub fn update(&mut self, env: &Environment, message: ListenerMessage) {
         match message {
+            ListenerMessage::TranscriptPath(path) => self.transcript_path = path,
+            ListenerMessage::Dribble => self.dribble(),
+            ListenerMessage::SaveTranscript => self.save_transcript(),
+            ListenerMessage::Paste(text) => {
+                if let Some(text) = text {
+                    self.paste(env, &text)
+                }
+            }
+            ListenerMessage::Complete(symbol) => {
+                self.completions.clear();
+                self.editor.replace_prefix(&symbol);
+                self.echo()
+            }
+            ListenerMessage::EventOccurred(Event::Keyboard(KeyPressed {
+                key_code: KeyCode::LShift | KeyCode::RShift,
+                ..
+            })) => (),
             ListenerMessage::EventOccurred(event) => match event {
+                Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                    let lines = match delta {
+                        mouse::ScrollDelta::Lines { y, .. } => y * 3.0,
+                        mouse::ScrollDelta::Pixels { y, .. } => y / 20.0,
+                    };
+
+                    if lines > 0.0 {
+                        self.tty.scroll_up(lines.round() as usize)
+                    } else {
+                        self.tty.scroll_down((-lines).round() as usize)
+                    }
+                }
+                Event::Keyboard(KeyPressed {
+                    key_code: KeyCode::PageUp,
+                    ..
+                }) => self.tty.page_up(),
+                Event::Keyboard(KeyPressed {
+                    key_code: KeyCode::PageDown,
+                    ..
+                }) => self.tty.page_down(),
+                Event::Keyboard(key_event) if self.find.is_some() => self.find_event(&key_event),
+                Event::Keyboard(KeyPressed { .. } | CharacterReceived(_))
+                    if !self.completions.is_empty() =>
+                {
+                    self.completions.clear();
+                    self.update(env, ListenerMessage::EventOccurred(event))
+                }
+                Event::Keyboard(key_event) if self.search.is_some() => {
+                    if !self.search_event(&key_event) {
+                        self.update(
+                            env,
+                            ListenerMessage::EventOccurred(Event::Keyboard(key_event)),
+                        )
+                    }
+
+                    self.echo()
+                }
                 Event::Keyboard(key_event) => match key_event {
-                    CharacterReceived(ch) => match ch {
-                        '\r' | '\n' => {
-                            self.tty.scroll();
-                            match self.eval(env, &self.command) {
-                                Ok(string) => self.tty.write_string(string),
-                                Err(e) => self.tty.write_string(Self::error(env, e)),
+                    KeyPressed {
+                        key_code,
+                        modifiers,
+                    } => {
+                        let word = modifiers.control() || modifiers.alt();
+
+                        match key_code {
+                            KeyCode::Left if word => self.editor.word_left(),
+                            KeyCode::Right if word => self.editor.word_right(),
+                            KeyCode::Left => self.editor.left(),
+                            KeyCode::Right => self.editor.right(),
+                            KeyCode::Home => self.editor.home(),
+                            KeyCode::End => self.editor.end(),
+                            KeyCode::Delete => self.editor.delete(),
+                            KeyCode::Escape if !self.command.is_empty() => {
+                                self.command.clear();
+                                self.editor.clear();
+                                self.tty.scroll()
                             }
-                            self.tty.scroll();
-                            self.tty.write_string("core> ".to_string());
+                            KeyCode::Up => {
+                                if !self.history.is_navigating() {
+                                    self.stash = self.editor.text()
+                                }
 
-                            self.command.clear();
+                                if let Some(entry) = self.history.older() {
+                                    self.editor.set(entry)
+                                }
+                            }
+                            KeyCode::Down if self.history.is_navigating() => {
+                                match self.history.newer() {
+                                    Some(entry) => self.editor.set(entry),
+                                    None => self.editor.set(&self.stash),
+                                }
+                            }
+                            _ => return,
                         }
+
+                        self.echo()
+                    }
+                    CharacterReceived(ch) if self.tty.is_scrolled() && !ch.is_control() => {
+                        self.tty.scroll_to_bottom();
+                        self.update(
+                            env,
+                            ListenerMessage::EventOccurred(Event::Keyboard(CharacterReceived(ch))),
+                        )
+                    }
+                    CharacterReceived(ch) => match ch {
+                        '\r' | '\n' => self.enter(env),
                         '\u{c}' => {
                             self.command.clear();
+                            self.editor.clear();
                             self.tty.clear();
-                            self.tty.write_string("core> ".to_string());
+                            self.prompt = Self::ns_prompt(&self.prompt_format, &self.ns);
+                            self.echo()
                         }
                         '\u{8}' => {
-                            if !self.command.is_empty() {
-                                self.command.pop().unwrap();
-                            }
-                            self.tty.backspace();
+                            self.editor.backspace();
+                            self.echo()
+                        }
+                        // ^A, ^E, ^D, ^K
+                        '\u{1}' => {
+                            self.editor.home();
+                            self.echo()
+                        }
+                        '\u{5}' => {
+                            self.editor.end();
+                            self.echo()
+                        }
+                        '\u{4}' => {
+                            self.editor.delete();
+                            self.echo()
+                        }
+                        '\u{b}' => {
+                            self.editor.kill();
+                            self.echo()
+                        }
+                        '\t' => {
+                            self.complete(env);
+                            self.echo()
                         }
+                        // ^F
+                        '\u{6}' => self.find_pattern(String::new()),
+                        // ^R
+                        '\u{12}' => {
+                            self.search = Some(Search {
+                                pattern: String::new(),
+                                found: None,
+                            });
+                            self.echo()
+                        }
+                        _ if ch.is_control() => (),
                         _ => {
-                            self.command.push(ch);
-                            self.tty.write_char(ch);
+                            self.editor.insert(ch);
+                            self.echo()
                         }
                     },
                     _ => (),
@@ -128,25 +973,134 @@ impl ListenerTab {
         subscription::events().map(ListenerMessage::EventOccurred)
     }
 
-    pub fn view(&self, _env: &Environment) -> Element<'_, Message, Renderer> {
-        let content: Element<'_, ListenerMessage> = Container::new(
-            Column::new()
-                .max_width(800)
-                .padding(20)
-                .spacing(10)
-                .push(text("listener".to_string()).size(20))
-                .push(horizontal_rule(1))
-                .push(Column::new().push(text(self.tty.contents())).height(500))
-                .push(horizontal_rule(1))
-                .width(800),
-        )
-        .width(Length::Fill)
-        .height(Length::Fill)
-        .align_x(Horizontal::Left)
-        .align_y(Vertical::Top)
-        .into();
-
-        content.map(Message::Listener)
+    fn completions(&self) -> Element<'_, ListenerMessage> {
+        let candidates = self
+            .completions
+            .iter()
+            .take(Self::COMPLETIONS)
+            .map(|symbol| {
+                button(text(symbol).size(14))
+                    .padding(2)
+                    .style(theme::Button::Text)
+                    .on_press(ListenerMessage::Complete(symbol.to_string()))
+                    .into()
+            })
+            .collect::<Vec<Element<'_, ListenerMessage>>>();
+
+        let more = self.completions.len().saturating_sub(Self::COMPLETIONS);
+
+        Column::with_children(candidates)
+            .push(if more > 0 {
+                text(format!("... {} more", more)).size(14)
+            } else {
+                text("")
+            })
+            .into()
+    }
+
+    fn transcript(&self) -> Element<'_, ListenerMessage> {
+        let dribble = if self.transcript.dribble_path().is_some() {
+            "stop dribble"
+        } else {
+            "dribble"
+        };
+
+        Row::new()
+            .spacing(10)
+            .align_items(Alignment::Center)
+            .push(
+                text_input(
+                    "transcript file (.txt or .md)",
+                    &self.transcript_path,
+                    ListenerMessage::TranscriptPath,
+                )
+                .size(14)
+                .width(300),
+            )
+            .push(
+                button(text(dribble).size(14))
+                    .padding(4)
+                    .on_press(ListenerMessage::Dribble),
+            )
+            .push(
+                button(text("save transcript").size(14))
+                    .padding(4)
+                    .on_press(ListenerMessage::SaveTranscript),
+            )
+            .push(text(&self.transcript_status).size(14))
+            .into()
+    }
+
+    fn sessions(&self, id: usize) -> Element<'_, Message> {
+        let mut row = Row::new()
+            .spacing(10)
+            .align_items(Alignment::Center)
+            .push(text(&self.name).size(20).width(Length::Fill))
+            .push(
+                button(text("new session").size(14))
+                    .padding(4)
+                    .on_press(Message::NewSession(false)),
+            )
+            .push(
+                button(text("new session, fresh core").size(14))
+                    .padding(4)
+                    .on_press(Message::NewSession(true)),
+            );
+
+        // the first listener is always there
+        if id > 0 {
+            row = row.push(
+                button(text("close").size(14))
+                    .padding(4)
+                    .on_press(Message::CloseSession(id)),
+            )
+        }
+
+        row.into()
+    }
+
+    pub fn view(&self, _env: &Environment, id: usize) -> Element<'_, Message, Renderer> {
+        let to_ui = move |message| Message::Listener(id, message);
+        let mut column = Column::new()
+            .max_width(Self::WIDTH)
+            .padding(20)
+            .spacing(10)
+            .push(self.sessions(id))
+            .push(horizontal_rule(1))
+            .push(
+                Column::new()
+                    .push(
+                        self.tty
+                            .view(Message::Select, Message::InspectValue, Message::Resize),
+                    )
+                    .height(500),
+            )
+            .push(horizontal_rule(1))
+            .push(self.transcript().map(to_ui))
+            .width(Self::WIDTH);
+
+        if let Some(find) = &self.find {
+            column = column.push(
+                text(format!(
+                    "find: {}  {}/{}",
+                    find.pattern,
+                    find.nth.map_or(0, |nth| nth + 1),
+                    find.lines.len()
+                ))
+                .size(14),
+            )
+        }
+
+        if !self.completions.is_empty() {
+            column = column.push(self.completions().map(to_ui))
+        }
+
+        Container::new(column)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(Horizontal::Left)
+            .align_y(Vertical::Top)
+            .into()
     }
 }
 
@@ -158,7 +1112,7 @@ impl Tab for ListenerTab {
     }
 
     fn tab_label(&self) -> TabLabel {
-        TabLabel::Text("listener".to_string())
+        TabLabel::Text(self.name.clone())
     }
 
     fn content(&self) -> Element<'_, Self::Message> {
@@ -1,6 +1,14 @@
 pub mod about;
+pub mod ansi;
+pub mod buffer;
+pub mod completion;
+pub mod editor;
 pub mod eth;
+pub mod history;
 pub mod inspector;
 pub mod listener;
 pub mod scratchpad;
+pub mod selection;
+pub mod syntax;
+pub mod transcript;
 pub mod tty;
@@ -8,9 +8,10 @@
 use {
     super::super::{
         super::{Core, Environment},
+        widgets::terminal::{Line, Span, Terminal},
         window::{Message, Tab},
     },
-    super::eth::CoreButton,
+    super::{buffer::TextBuffer, eth::CoreButton},
     iced::{
         alignment::{self, Horizontal, Vertical},
         executor, subscription, theme,
@@ -25,8 +26,7 @@ use {
 // components
 #[derive(Debug, Default)]
 pub struct ControlInfo {
-    image: RwLock<String>,
-    lines: RwLock<Vec<String>>,
+    buffer: RwLock<TextBuffer>,
     rows: usize,
     cols: usize,
 }
@@ -34,99 +34,52 @@ pub struct ControlInfo {
 impl ControlInfo {
     pub fn new(rows: usize, cols: usize) -> Self {
         ControlInfo {
-            image: RwLock::new(String::new()),
-            lines: RwLock::new(vec![String::new(); rows]),
+            buffer: RwLock::new(TextBuffer::new(rows, 0)),
             rows,
             cols,
         }
     }
 
-    fn collapse(&self) {
-        let mut image = self.image.write().unwrap();
-        let lines = self.lines.read().unwrap();
-
-        let mut img = String::new();
-
-        for line in &lines[0..self.rows - 1] {
-            if line.is_empty() {
-                img.push_str(" \n");
-            } else {
-                img.push_str(line);
-                img.push('\n')
-            }
-        }
-
-        if lines[self.rows - 1].is_empty() {
-            img.push(' ');
-        } else {
-            img.push_str(&lines[self.rows - 1]);
-        }
-
-        *image = img
-    }
-
     pub fn clear(&self) {
-        {
-            let mut lines = self.lines.write().unwrap();
-
-            *lines = vec![String::new(); self.rows]
-        }
-
-        self.collapse()
+        self.buffer.write().unwrap().clear()
     }
 
     pub fn scroll(&self) {
-        {
-            let mut lines = self.lines.write().unwrap();
-
-            lines.remove(0);
-            lines.push(String::new())
-        }
-
-        self.collapse()
+        self.buffer.write().unwrap().scroll()
     }
 
     pub fn backspace(&self) {
-        {
-            let mut lines = self.lines.write().unwrap();
-
-            if !lines[self.rows - 1].is_empty() {
-                lines[self.rows - 1].pop().unwrap();
-            }
-        }
-
-        self.collapse()
+        self.buffer.write().unwrap().backspace();
     }
 
     pub fn write_char(&self, ch: char) {
-        {
-            let mut lines = self.lines.write().unwrap();
-
-            lines[self.rows - 1].push(ch)
-        }
-
-        self.collapse()
+        self.write(ch.to_string())
     }
 
     pub fn write(&self, str: String) {
-        {
-            let mut lines = self.lines.write().unwrap();
-
-            lines[self.rows - 1].push_str(&str)
-        }
-
-        self.collapse()
+        self.buffer.write().unwrap().append(Span::plain(&str))
     }
 
     pub fn contents(&self) -> String {
-        let image = self.image.read().unwrap();
-
-        image.clone()
+        self.buffer.read().unwrap().contents()
     }
 
     pub fn content(&self) -> Element<'_, ScratchpadMessage> {
-        let content: Element<'_, ScratchpadMessage> =
-            Column::new().push(text(self.contents())).into();
+        let lines = self
+            .buffer
+            .read()
+            .unwrap()
+            .screen()
+            .enumerate()
+            .map(|(id, line)| Line {
+                id,
+                spans: line.to_vec(),
+                selected: None,
+                stamp: None,
+            })
+            .collect();
+
+        let content: Element<'_, ScratchpadMessage> = Terminal::new(lines, self.rows).into();
 
         container(content)
             .width(Length::Fill)
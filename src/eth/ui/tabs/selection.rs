@@ -0,0 +1,144 @@
+//  SPDX-FileCopyrightText: Copyright 2023 James M. Putnam (putnamjm.design@gmail.com)
+//  SPDX-License-Identifier: MIT
+
+// mouse selection for the consoles
+//
+#![allow(clippy::new_without_default)]
+use std::sync::RwLock;
+
+// where the mouse went down and where it is now, as line and column. a
+// click selects whole lines, dragging selects the text in between
+#[derive(Debug, Clone, Copy)]
+struct Range {
+    anchor: (usize, usize),
+    end: (usize, usize),
+    lines: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct Selection {
+    range: RwLock<Option<Range>>,
+}
+
+impl Selection {
+    pub fn new() -> Self {
+        Selection {
+            range: RwLock::new(None),
+        }
+    }
+
+    // select the line at, or extend the selection to it
+    pub fn select(&self, at: (usize, usize), extend: bool) {
+        let mut range = self.range.write().unwrap();
+
+        *range = match *range {
+            Some(range) if extend => Some(Range { end: at, ..range }),
+            _ => Some(Range {
+                anchor: at,
+                end: at,
+                lines: true,
+            }),
+        }
+    }
+
+    // select the text from where the mouse went down to at
+    pub fn drag(&self, at: (usize, usize)) {
+        if let Some(range) = self.range.write().unwrap().as_mut() {
+            range.end = at;
+            range.lines = false
+        }
+    }
+
+    pub fn clear(&self) {
+        *self.range.write().unwrap() = None
+    }
+
+    // first and last selected lines
+    pub fn lines(&self) -> Option<(usize, usize)> {
+        let (start, end) = self.ends()?;
+
+        Some((start.0, end.0))
+    }
+
+    // the selected columns of line, the end is exclusive and usize::MAX
+    // runs to the end of the line
+    pub fn columns(&self, line: usize) -> Option<(usize, usize)> {
+        let range = (*self.range.read().unwrap())?;
+        let (start, end) = self.ends()?;
+
+        if line < start.0 || line > end.0 {
+            return None;
+        }
+
+        Some(match range.lines {
+            true => (0, usize::MAX),
+            false => (
+                if line == start.0 { start.1 } else { 0 },
+                if line == end.0 {
+                    end.1.saturating_add(1)
+                } else {
+                    usize::MAX
+                },
+            ),
+        })
+    }
+
+    pub fn contains(&self, line: usize) -> bool {
+        self.columns(line).is_some()
+    }
+
+    // the selected part of line's text
+    pub fn text(&self, line: usize, text: &str) -> Option<String> {
+        let (start, end) = self.columns(line)?;
+
+        Some(text.chars().skip(start).take(end - start).collect())
+    }
+
+    fn ends(&self) -> Option<((usize, usize), (usize, usize))> {
+        let range = (*self.range.read().unwrap())?;
+
+        Some((range.anchor.min(range.end), range.anchor.max(range.end)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Selection;
+
+    #[test]
+    fn lines() {
+        let selection = Selection::new();
+
+        assert_eq!(selection.lines(), None);
+        selection.select((3, 4), false);
+        selection.select((1, 2), true);
+        assert_eq!(selection.lines(), Some((1, 3)));
+        assert_eq!(selection.text(2, "abc").as_deref(), Some("abc"));
+        assert_eq!(selection.text(4, "abc"), None);
+
+        selection.clear();
+        assert!(!selection.contains(2));
+    }
+
+    #[test]
+    fn drag() {
+        let selection = Selection::new();
+
+        selection.select((2, 3), false);
+        selection.drag((0, 1));
+        assert_eq!(selection.columns(0), Some((1, usize::MAX)));
+        assert_eq!(selection.columns(1), Some((0, usize::MAX)));
+        assert_eq!(selection.columns(2), Some((0, 4)));
+        assert_eq!(selection.text(0, "abc").as_deref(), Some("bc"));
+        assert_eq!(selection.text(2, "abcdef").as_deref(), Some("abcd"));
+
+        // on one line the ends can come in either order
+        selection.select((5, 4), false);
+        selection.drag((5, 2));
+        assert_eq!(selection.text(5, "abcdef").as_deref(), Some("cde"));
+
+        // dragging past the end takes the rest of the line
+        selection.drag((5, 40));
+        assert_eq!(selection.text(5, "abcdef").as_deref(), Some("ef"));
+    }
+}
@@ -0,0 +1,331 @@
+//  SPDX-FileCopyrightText: Copyright 2023 James M. Putnam (putnamjm.design@gmail.com)
+//  SPDX-License-Identifier: MIT
+
+// listener form syntax
+//
+#![allow(dead_code)]
+
+// reader state at the end of some partial input
+#[derive(Debug, Default)]
+pub struct FormState {
+    // line columns of the unclosed open parens, innermost last
+    pub open: Vec<usize>,
+    pub string: bool,
+    pub comment: bool,
+}
+
+impl FormState {
+    // extra close parens are left for the reader to complain about
+    pub fn is_complete(&self) -> bool {
+        self.open.is_empty() && !self.string && !self.comment
+    }
+
+    // indentation for a continuation line, none inside a string or
+    // comment where it would change the text
+    pub fn indent(&self) -> usize {
+        match self.open.last() {
+            _ if self.string || self.comment => 0,
+            Some(column) => column + 2,
+            None => 0,
+        }
+    }
+}
+
+// the reader state follows from the tokens, the parens still open at the
+// end and whether the last token is an unterminated string or comment
+pub fn scan(text: &str) -> FormState {
+    let chars = text.chars().collect::<Vec<_>>();
+    let (tokens, unclosed) = lex(&chars);
+    let mut state = FormState {
+        open: Vec::new(),
+        string: unclosed == Some(Class::String),
+        comment: unclosed == Some(Class::Comment),
+    };
+    let mut line = 0;
+
+    for token in tokens {
+        if token.class == Class::Paren {
+            if chars[token.start] == '(' {
+                state.open.push(token.start - line)
+            } else {
+                state.open.pop();
+            }
+        }
+
+        if let Some(newline) = chars[token.start..token.end]
+            .iter()
+            .rposition(|ch| *ch == '\n')
+        {
+            line = token.start + newline + 1
+        }
+    }
+
+    state
+}
+
+pub fn is_complete(text: &str) -> bool {
+    scan(text).is_complete()
+}
+
+// a lone number, string, keyword or char evaluates to itself
+pub fn is_self_evaluating(text: &str) -> bool {
+    match tokenize(text.trim()).as_slice() {
+        [token] => matches!(
+            token.class,
+            Class::Number | Class::String | Class::Keyword | Class::Char
+        ),
+        _ => false,
+    }
+}
+
+// syntax classes for highlighting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Class {
+    Char,
+    Comment,
+    Keyword,
+    Number,
+    Paren,
+    Quote,
+    Special,
+    String,
+    Symbol,
+    Whitespace,
+}
+
+// a token's class and its char offsets in the text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub class: Class,
+    pub start: usize,
+    pub end: usize,
+}
+
+const SPECIAL_FORMS: &[&str] = &[":lambda", ":if", ":quote", ":macro"];
+
+fn is_constituent(ch: char) -> bool {
+    !(ch.is_whitespace() || "()'`,\";".contains(ch))
+}
+
+// rust parses inf and nan as floats, the reader doesn't
+fn is_number(atom: &str) -> bool {
+    let digits = atom.trim_start_matches(['+', '-']).trim_start_matches('.');
+
+    digits.starts_with(|ch: char| ch.is_ascii_digit())
+        && (atom.parse::<i64>().is_ok() || atom.parse::<f64>().is_ok())
+}
+
+fn atom_class(atom: &str) -> Class {
+    if SPECIAL_FORMS.contains(&atom) {
+        Class::Special
+    } else if atom.starts_with(':') {
+        Class::Keyword
+    } else if is_number(atom) {
+        Class::Number
+    } else {
+        Class::Symbol
+    }
+}
+
+// unclosed strings and comments run to the end of the text
+pub fn tokenize(text: &str) -> Vec<Token> {
+    lex(&text.chars().collect::<Vec<_>>()).0
+}
+
+// the tokens, and the class of the last one if it's an unclosed string or comment
+fn lex(chars: &[char]) -> (Vec<Token>, Option<Class>) {
+    let mut tokens = Vec::new();
+    let mut unclosed = None;
+    let mut at = 0;
+
+    while at < chars.len() {
+        let start = at;
+        let ch = chars[at];
+
+        at += 1;
+        let class = match ch {
+            '(' | ')' => Class::Paren,
+            '\'' | '`' | ',' => Class::Quote,
+            _ if ch.is_whitespace() => {
+                while at < chars.len() && chars[at].is_whitespace() {
+                    at += 1
+                }
+                Class::Whitespace
+            }
+            '"' => {
+                while at < chars.len() && chars[at] != '"' {
+                    at += if chars[at] == '\\' { 2 } else { 1 }
+                }
+                if at >= chars.len() {
+                    unclosed = Some(Class::String)
+                }
+                at = (at + 1).min(chars.len());
+                Class::String
+            }
+            ';' => {
+                while at < chars.len() && chars[at] != '\n' {
+                    at += 1
+                }
+                Class::Comment
+            }
+            '#' if at < chars.len() && chars[at] == '|' => {
+                at += 2;
+                while at < chars.len() && !(chars[at - 1] == '|' && chars[at] == '#') {
+                    at += 1
+                }
+                if at >= chars.len() {
+                    unclosed = Some(Class::Comment)
+                }
+                at = (at + 1).min(chars.len());
+                Class::Comment
+            }
+            '#' if at < chars.len() && chars[at] == '\\' => {
+                at = (at + 2).min(chars.len());
+                while at < chars.len() && is_constituent(chars[at]) {
+                    at += 1
+                }
+                Class::Char
+            }
+            _ => {
+                while at < chars.len() && is_constituent(chars[at]) {
+                    at += 1
+                }
+                atom_class(&chars[start..at].iter().collect::<String>())
+            }
+        };
+
+        tokens.push(Token {
+            class,
+            start,
+            end: at,
+        })
+    }
+
+    (tokens, unclosed)
+}
+
+// char offset of the paren matching the one at offset
+pub fn matching_paren(tokens: &[Token], text: &[char], offset: usize) -> Option<usize> {
+    let nth = tokens
+        .iter()
+        .position(|token| token.class == Class::Paren && token.start == offset)?;
+
+    let parens = tokens.iter().filter(|token| token.class == Class::Paren);
+    let mut depth = 0;
+
+    if text[offset] == '(' {
+        for token in parens.filter(|token| token.start >= tokens[nth].start) {
+            depth += if text[token.start] == '(' { 1 } else { -1 };
+            if depth == 0 {
+                return Some(token.start);
+            }
+        }
+    } else {
+        let before = parens
+            .filter(|token| token.start <= tokens[nth].start)
+            .collect::<Vec<_>>();
+
+        for token in before.iter().rev() {
+            depth += if text[token.start] == ')' { 1 } else { -1 };
+            if depth == 0 {
+                return Some(token.start);
+            }
+        }
+    }
+
+    None
+}
+
+// char offsets of parens without a partner
+pub fn unmatched_parens(tokens: &[Token], text: &[char]) -> Vec<usize> {
+    let mut open = Vec::new();
+    let mut unmatched = Vec::new();
+
+    for token in tokens.iter().filter(|token| token.class == Class::Paren) {
+        if text[token.start] == '(' {
+            open.push(token.start)
+        } else if open.pop().is_none() {
+            unmatched.push(token.start)
+        }
+    }
+
+    unmatched.extend(open);
+    unmatched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_self_evaluating, scan, tokenize, Class};
+
+    #[test]
+    fn balanced() {
+        assert!(scan("").is_complete());
+        assert!(scan("(+ 1 2)").is_complete());
+        assert!(scan("(+ 1 2) ; (note").is_complete());
+        assert!(scan("#\\( #\\)").is_complete());
+
+        // extra close parens are complete, the reader reports them
+        assert!(scan("(a))").is_complete());
+    }
+
+    #[test]
+    fn open_parens() {
+        let state = scan("(:lambda (x)\n  (f x");
+
+        assert!(!state.is_complete());
+        assert_eq!(state.open, vec![0, 2]);
+        assert_eq!(state.indent(), 4);
+
+        // parens in comments, strings and chars don't count
+        assert_eq!(scan("(f ; )\n").open, vec![0]);
+        assert_eq!(scan("(f \")\" #\\)").open, vec![0]);
+    }
+
+    #[test]
+    fn strings() {
+        let state = scan("(f \"abc");
+
+        assert!(state.string);
+        assert!(!state.is_complete());
+        assert_eq!(state.indent(), 0);
+
+        assert!(scan("(f \"a\\\"b\")").is_complete());
+        assert!(scan("\"a\nb\"").is_complete());
+    }
+
+    #[test]
+    fn comments() {
+        let state = scan("(f #| a\n(b");
+
+        assert!(state.comment);
+        assert!(!state.is_complete());
+        assert_eq!(state.indent(), 0);
+
+        assert!(scan("#| (a |# (b)").is_complete());
+        assert!(scan("#||#").is_complete());
+    }
+
+    #[test]
+    fn numbers() {
+        let class = |atom| tokenize(atom)[0].class;
+
+        for number in ["1", "-2.5", ".5", "1e3"] {
+            assert_eq!(class(number), Class::Number)
+        }
+
+        for symbol in ["inf", "nan", "-infinity", "1+"] {
+            assert_eq!(class(symbol), Class::Symbol)
+        }
+    }
+
+    #[test]
+    fn self_evaluating() {
+        for atom in ["1", "\"a b\"", ":t", "#\\a"] {
+            assert!(is_self_evaluating(atom), "{}", atom)
+        }
+
+        for form in ["foo", "(1 2)", "1 2", "#(:t 1)"] {
+            assert!(!is_self_evaluating(form), "{}", form)
+        }
+    }
+}
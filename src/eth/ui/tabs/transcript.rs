@@ -0,0 +1,98 @@
+//  SPDX-FileCopyrightText: Copyright 2023 James M. Putnam (putnamjm.design@gmail.com)
+//  SPDX-License-Identifier: MIT
+
+// listener transcript and dribble file
+//
+#![allow(clippy::new_without_default)]
+#![allow(dead_code)]
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone)]
+pub enum Entry {
+    Input { prompt: String, form: String },
+    Output(String),
+}
+
+// .md files get markdown with fenced code blocks, anything else is plain text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Markdown,
+}
+
+impl Format {
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("md") | Some("markdown") => Format::Markdown,
+            _ => Format::Text,
+        }
+    }
+
+    pub fn entry(&self, entry: &Entry) -> String {
+        match (self, entry) {
+            (Format::Text, Entry::Input { prompt, form }) => format!("{}{}\n", prompt, form),
+            (Format::Text, Entry::Output(output)) => format!("{}\n", output),
+            (Format::Markdown, Entry::Input { form, .. }) => format!("```lisp\n{}\n```\n", form),
+            (Format::Markdown, Entry::Output(output)) => format!("```\n{}\n```\n\n", output),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Transcript {
+    entries: Vec<Entry>,
+    dribble: Option<(PathBuf, Format)>,
+}
+
+impl Transcript {
+    pub fn new() -> Self {
+        Transcript {
+            entries: Vec::new(),
+            dribble: None,
+        }
+    }
+
+    // record an entry, appending it to the dribble file if there is one
+    pub fn push(&mut self, entry: Entry) -> io::Result<()> {
+        let dribble = match &self.dribble {
+            Some((path, format)) => OpenOptions::new()
+                .append(true)
+                .open(path)
+                .and_then(|mut file| file.write_all(format.entry(&entry).as_bytes())),
+            None => Ok(()),
+        };
+
+        self.entries.push(entry);
+        dribble
+    }
+
+    pub fn dribble(&mut self, path: &Path) -> io::Result<()> {
+        OpenOptions::new().create(true).append(true).open(path)?;
+
+        self.dribble = Some((path.to_path_buf(), Format::from_path(path)));
+        Ok(())
+    }
+
+    pub fn stop_dribble(&mut self) {
+        self.dribble = None
+    }
+
+    pub fn dribble_path(&self) -> Option<&Path> {
+        self.dribble.as_ref().map(|(path, _)| path.as_path())
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let format = Format::from_path(path);
+        let mut file = File::create(path)?;
+
+        for entry in &self.entries {
+            file.write_all(format.entry(entry).as_bytes())?
+        }
+
+        Ok(())
+    }
+}
@@ -2,44 +2,96 @@
 //  SPDX-License-Identifier: MIT
 #![allow(unused_imports)]
 
-use {crate::Environment, std::sync::RwLock};
+pub use super::super::widgets::terminal::{Cursor, Span};
+use {
+    super::{
+        super::widgets::terminal::{Line, Terminal},
+        ansi::{Action, Parser},
+        buffer::TextBuffer,
+        selection::Selection,
+    },
+    crate::Environment,
+    chrono::{DateTime, Local},
+    iced::{Color, Element, Renderer},
+    std::sync::RwLock,
+};
 
 #[derive(Debug, Default)]
 pub struct Tty {
-    image: RwLock<String>,
-    lines: RwLock<Vec<String>>,
+    buffer: RwLock<TextBuffer>,
+    // display rows scrolled up from the bottom, lines wrap at columns
+    offset: RwLock<usize>,
+    columns: RwLock<usize>,
+    highlight: RwLock<Option<String>>,
+    // anchor and end, counted from the first line ever written so
+    // they stay put when the scrollback drops lines
+    selection: Selection,
+    column: RwLock<Option<usize>>,
+    // escape sequence state and the output cursor, None is the end of the last line
+    parser: RwLock<Parser>,
+    position: RwLock<Option<(usize, usize)>>,
     rows: usize,
-    cursor: char,
+    cursor: Cursor,
+    // lines show the time they were written in a gutter
+    timestamps: bool,
 }
 
 #[derive(Debug, Default)]
 pub struct TtyBuilder {
     rows: Option<usize>,
-    cursor: Option<char>,
+    scrollback: Option<usize>,
+    cursor: Option<Cursor>,
+    timestamps: bool,
 }
 
 impl TtyBuilder {
     const ROWS: usize = 25;
-    const CURSOR: char = '\u{00ab}';
+    const SCROLLBACK: usize = 1000;
+    const CURSOR: Cursor = Cursor::Block;
 
     pub fn new() -> Self {
         TtyBuilder {
             rows: None,
+            scrollback: None,
             cursor: None,
+            timestamps: false,
         }
     }
 
     pub fn rows(&self, rows: usize) -> Self {
         TtyBuilder {
             rows: Some(rows),
+            scrollback: self.scrollback,
             cursor: self.cursor,
+            timestamps: self.timestamps,
         }
     }
 
-    pub fn cursor(&self, cursor: char) -> Self {
+    pub fn scrollback(&self, scrollback: usize) -> Self {
         TtyBuilder {
             rows: self.rows,
+            scrollback: Some(scrollback),
+            cursor: self.cursor,
+            timestamps: self.timestamps,
+        }
+    }
+
+    pub fn cursor(&self, cursor: Cursor) -> Self {
+        TtyBuilder {
+            rows: self.rows,
+            scrollback: self.scrollback,
             cursor: Some(cursor),
+            timestamps: self.timestamps,
+        }
+    }
+
+    // show the time each line was first written next to it
+    pub fn timestamps(&self, timestamps: bool) -> Self {
+        TtyBuilder {
+            rows: self.rows,
+            scrollback: self.scrollback,
+            cursor: self.cursor,
+            timestamps,
         }
     }
 
@@ -49,110 +101,432 @@ impl TtyBuilder {
             None => Self::ROWS,
         };
 
+        let scrollback = match self.scrollback {
+            Some(scrollback) => scrollback,
+            None => Self::SCROLLBACK,
+        };
+
         Tty {
-            image: RwLock::new(String::new()),
-            lines: RwLock::new(vec![String::new(); rows]),
+            buffer: RwLock::new(TextBuffer::new(rows, scrollback).timestamps(self.timestamps)),
+            offset: RwLock::new(0),
+            columns: RwLock::new(usize::MAX),
+            highlight: RwLock::new(None),
+            selection: Selection::new(),
+            column: RwLock::new(None),
+            parser: RwLock::new(Parser::new()),
+            position: RwLock::new(None),
             rows,
             cursor: match self.cursor {
                 Some(cursor) => cursor,
                 None => Self::CURSOR,
             },
+            timestamps: self.timestamps,
         }
     }
 }
 
 impl Tty {
+    // the output cursor stops here, escape sequences can ask for any column
+    const MAX_COLUMN: usize = 4096;
+
+    // HH:MM:SS and a blank
+    const GUTTER: usize = 9;
+
     pub fn new(rows: usize) -> Self {
-        Tty {
-            image: RwLock::new(String::new()),
-            lines: RwLock::new(vec![String::new(); rows]),
-            rows,
-            cursor: '\u{00ab}',
-        }
+        TtyBuilder::new().rows(rows).build()
+    }
+
+    fn line_width(line: &[Span]) -> usize {
+        line.iter().map(|span| span.text.chars().count()).sum()
     }
 
-    fn collapse(&self) {
-        let mut image = self.image.write().unwrap();
-        let lines = self.lines.read().unwrap();
+    // one char per cell with its style, for editing lines in place
+    fn cells(line: &[Span]) -> Vec<(char, Span)> {
+        line.iter()
+            .flat_map(|span| span.text.chars().map(move |ch| (ch, span.with_text(""))))
+            .collect()
+    }
 
-        let mut img = String::new();
+    fn merge(cells: Vec<(char, Span)>) -> Vec<Span> {
+        let mut spans: Vec<Span> = Vec::new();
 
-        for line in &lines[0..self.rows - 1] {
-            if line.is_empty() {
-                img.push_str(" \n");
-            } else {
-                img.push_str(line);
-                img.push('\n')
+        for (ch, style) in cells {
+            match spans.last_mut() {
+                Some(span) if span.same_style(&style) => span.text.push(ch),
+                _ => spans.push(style.with_text(&ch.to_string())),
             }
         }
 
-        if lines[self.rows - 1].is_empty() {
-            img.push(' ');
-        } else {
-            img.push_str(&lines[self.rows - 1]);
+        spans
+    }
+
+    // overwrite the cell at column, padding with blanks
+    fn put(line: &mut Vec<Span>, column: usize, span: Span) {
+        let mut cells = Self::cells(line);
+
+        while cells.len() < column {
+            cells.push((' ', Span::default()))
         }
 
-        img.push(self.cursor);
+        for (nth, ch) in span.text.chars().enumerate() {
+            let cell = (ch, span.with_text(""));
+
+            match cells.get_mut(column + nth) {
+                Some(old) => *old = cell,
+                None => cells.push(cell),
+            }
+        }
 
-        *image = img
+        *line = Self::merge(cells)
     }
 
     pub fn clear(&self) {
-        {
-            let mut lines = self.lines.write().unwrap();
+        self.buffer.write().unwrap().clear();
+        *self.column.write().unwrap() = None;
+        *self.offset.write().unwrap() = 0;
+        self.selection.clear();
+        *self.position.write().unwrap() = None
+    }
 
-            *lines = vec![String::new(); self.rows]
-        }
+    pub fn scroll(&self) {
+        self.buffer.write().unwrap().scroll();
+        *self.column.write().unwrap() = None;
+        *self.position.write().unwrap() = None
+    }
 
-        self.collapse()
+    pub fn backspace(&self) {
+        self.buffer.write().unwrap().backspace();
     }
 
-    pub fn scroll(&self) {
-        {
-            let mut lines = self.lines.write().unwrap();
+    pub fn write_char(&self, ch: char) {
+        self.write_string(ch.to_string())
+    }
 
-            lines.remove(0);
-            lines.push(String::new())
-        }
+    // text with escape sequences, written at the output cursor
+    pub fn write_string(&self, str: String) {
+        self.write(&str, &Span::default())
+    }
 
-        self.collapse()
+    // the span's text with escape sequences, the span styles what the
+    // sequences leave unset and its link goes on every span printed
+    pub fn write_span(&self, span: Span) {
+        self.write(&span.text, &span)
     }
 
-    pub fn backspace(&self) {
-        {
-            let mut lines = self.lines.write().unwrap();
+    fn write(&self, str: &str, base: &Span) {
+        let mut parser = self.parser.write().unwrap();
+        let mut buffer = self.buffer.write().unwrap();
+        let mut position = self.position.write().unwrap();
+        let last = self.rows - 1;
 
-            if !lines[self.rows - 1].is_empty() {
-                lines[self.rows - 1].pop().unwrap();
+        let (mut row, mut column) = match *position {
+            Some(position) => position,
+            None => (last, Self::line_width(buffer.row(last))),
+        };
+        // the width of the cursor's row, so printing at its end can append
+        let mut width = Self::line_width(buffer.row(row));
+
+        for ch in str.chars() {
+            let action = match parser.feed(ch) {
+                Some(action) => action,
+                None => continue,
+            };
+
+            match action {
+                Action::Print(ch) => {
+                    let mut span = parser.style.span(&ch.to_string());
+
+                    span.color = span.color.or(base.color);
+                    span.background = span.background.or(base.background);
+                    span.underline |= base.underline;
+                    span.link = base.link;
+
+                    if column == width {
+                        buffer.append_to(row, span);
+                        width += 1
+                    } else {
+                        buffer.update_row(row, |line| Self::put(line, column, span));
+                        width = width.max(column + 1)
+                    }
+                    column = (column + 1).min(Self::MAX_COLUMN);
+                    continue;
+                }
+                Action::CarriageReturn => column = 0,
+                Action::LineFeed => {
+                    if row == last {
+                        buffer.scroll()
+                    } else {
+                        row += 1
+                    }
+                    column = 0
+                }
+                Action::Backspace => column = column.saturating_sub(1),
+                Action::CursorUp(count) => row = row.saturating_sub(count),
+                Action::CursorDown(count) => row = row.saturating_add(count).min(last),
+                Action::CursorForward(count) => {
+                    column = column.saturating_add(count).min(Self::MAX_COLUMN)
+                }
+                Action::CursorBack(count) => column = column.saturating_sub(count),
+                Action::CursorColumn(to) => column = to.min(Self::MAX_COLUMN),
+                Action::CursorPosition(to_row, to_column) => {
+                    row = to_row.min(last);
+                    column = to_column.min(Self::MAX_COLUMN)
+                }
+                Action::EraseLine(mode) => buffer.update_row(row, |line| {
+                    let mut cells = Self::cells(line);
+
+                    match mode {
+                        0 => cells.truncate(column),
+                        1 => {
+                            for cell in cells.iter_mut().take(column + 1) {
+                                *cell = (' ', Span::default())
+                            }
+                        }
+                        _ => cells.clear(),
+                    }
+                    *line = Self::merge(cells)
+                }),
+                Action::EraseScreen(mode) => {
+                    let erase = match mode {
+                        0 => {
+                            buffer.update_row(row, |line| {
+                                let mut cells = Self::cells(line);
+
+                                cells.truncate(column);
+                                *line = Self::merge(cells)
+                            });
+                            row + 1..self.rows
+                        }
+                        1 => 0..row,
+                        _ => 0..self.rows,
+                    };
+
+                    for nth in erase {
+                        buffer.update_row(nth, |line| line.clear())
+                    }
+                }
             }
+
+            // the rest move the cursor or erase
+            width = Self::line_width(buffer.row(row))
         }
 
-        self.collapse()
+        *position = Some((row, column))
     }
 
-    pub fn write_char(&self, ch: char) {
-        {
-            let mut lines = self.lines.write().unwrap();
+    // replace the input line, cursor at column
+    pub fn write_input(&self, str: String, column: usize) {
+        self.write_input_spans(vec![Span::plain(&str)], column)
+    }
+
+    pub fn write_input_spans(&self, spans: Vec<Span>, column: usize) {
+        self.buffer.write().unwrap().set_row(self.rows - 1, spans);
+        *self.column.write().unwrap() = Some(column)
+    }
 
-            lines[self.rows - 1].push(ch)
+    // the screen as text
+    pub fn contents(&self) -> String {
+        self.buffer.read().unwrap().contents()
+    }
+
+    // the terminal's width, lines wider than this take more than one row
+    pub fn resize(&self, columns: usize) {
+        *self.columns.write().unwrap() = columns.max(1)
+    }
+
+    // the display rows each line wraps to
+    fn heights(&self, buffer: &TextBuffer) -> Vec<usize> {
+        let columns = *self.columns.read().unwrap();
+
+        buffer
+            .iter()
+            .map(|line| 1 + Self::line_width(line).saturating_sub(1) / columns)
+            .collect()
+    }
+
+    // display rows above the screen
+    fn limit(&self, heights: &[usize]) -> usize {
+        heights.iter().sum::<usize>().saturating_sub(self.rows)
+    }
+
+    // scrollback navigation, counted in display rows
+    pub fn scroll_up(&self, rows: usize) {
+        let limit = self.limit(&self.heights(&self.buffer.read().unwrap()));
+        let mut offset = self.offset.write().unwrap();
+
+        *offset = offset.saturating_add(rows).min(limit)
+    }
+
+    pub fn scroll_down(&self, rows: usize) {
+        let mut offset = self.offset.write().unwrap();
+
+        *offset = offset.saturating_sub(rows)
+    }
+
+    pub fn page_up(&self) {
+        self.scroll_up(self.rows - 1)
+    }
+
+    pub fn page_down(&self) {
+        self.scroll_down(self.rows - 1)
+    }
+
+    pub fn scroll_to_bottom(&self) {
+        *self.offset.write().unwrap() = 0
+    }
+
+    pub fn is_scrolled(&self) -> bool {
+        *self.offset.read().unwrap() != 0
+    }
+
+    // scroll so that line, counted from the oldest scrollback line, is
+    // visible. a line that isn't all on screen goes to the top
+    pub fn show_line(&self, line: usize) {
+        let heights = self.heights(&self.buffer.read().unwrap());
+        let mut offset = self.offset.write().unwrap();
+
+        if line >= heights.len() {
+            return;
         }
 
-        self.collapse()
+        let below = heights[line + 1..].iter().sum::<usize>();
+        let bottom = below + heights[line];
+
+        if below < *offset || bottom > *offset + self.rows {
+            *offset = bottom.saturating_sub(self.rows).min(self.limit(&heights))
+        }
     }
 
-    pub fn write_string(&self, str: String) {
-        {
-            let mut lines = self.lines.write().unwrap();
+    // lines with a match for pattern, oldest first
+    pub fn find(&self, pattern: &str) -> Vec<usize> {
+        if pattern.is_empty() {
+            return Vec::new();
+        }
+
+        let buffer = self.buffer.read().unwrap();
+
+        buffer
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| TextBuffer::line_text(line).contains(pattern))
+            .map(|(nth, _)| nth)
+            .collect()
+    }
+
+    pub fn set_highlight(&self, pattern: Option<String>) {
+        *self.highlight.write().unwrap() = pattern.filter(|pattern| !pattern.is_empty())
+    }
+
+    // lines dropped from the scrollback so far
+    pub fn dropped(&self) -> usize {
+        self.buffer.read().unwrap().dropped()
+    }
+
+    // select a line, counted like show_line, or extend the selection to it
+    pub fn select(&self, line: usize, column: usize, extend: bool) {
+        self.selection
+            .select((line + self.dropped(), column), extend)
+    }
 
-            lines[self.rows - 1].push_str(&str)
+    pub fn drag(&self, line: usize, column: usize) {
+        self.selection.drag((line + self.dropped(), column))
+    }
+
+    pub fn clear_selection(&self) {
+        self.selection.clear()
+    }
+
+    pub fn selection(&self) -> Option<String> {
+        let (first, last) = self.selection.lines()?;
+        let buffer = self.buffer.read().unwrap();
+
+        // the part that's still in the scrollback
+        let first = first.max(buffer.dropped()) - buffer.dropped();
+        let last = last.checked_sub(buffer.dropped())?;
+
+        let selected = buffer
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(last + 1 - first)
+            .filter_map(|(nth, line)| {
+                self.selection
+                    .text(nth + buffer.dropped(), &TextBuffer::line_text(line))
+            })
+            .collect::<Vec<_>>();
+
+        Some(selected.join("\n"))
+    }
+
+    // recolor the matches for pattern
+    fn mark(line: &[Span], pattern: &str) -> Vec<Span> {
+        let text = TextBuffer::line_text(line);
+        let mut cells = Self::cells(line);
+
+        for (at, _) in text.match_indices(pattern) {
+            let start = text[..at].chars().count();
+
+            for (_, style) in &mut cells[start..start + pattern.chars().count()] {
+                style.color = Some(Color::from_rgb8(0xe0, 0x60, 0x00))
+            }
         }
 
-        self.collapse()
+        Self::merge(cells)
     }
 
-    pub fn contents(&self) -> String {
-        let image = self.image.read().unwrap();
+    // lines report their index and column for selection, links report
+    // theirs, and the terminal reports its width in columns
+    pub fn view<'a, Message: Clone + 'a>(
+        &self,
+        on_select: fn(usize, usize, bool) -> Message,
+        on_link: fn(usize) -> Message,
+        on_resize: fn(usize) -> Message,
+    ) -> Element<'a, Message, Renderer> {
+        let buffer = self.buffer.read().unwrap();
+        let column = *self.column.read().unwrap();
+        let offset = *self.offset.read().unwrap();
+        let highlight = self.highlight.read().unwrap();
+
+        let heights = self.heights(&buffer);
+        let input = buffer.history() + self.rows - 1;
+
+        // lines from the bottom up until they cover the rows scrolled and a screen
+        let mut top = heights.len();
+        let mut covered = 0;
+
+        while top > 0 && covered < offset + self.rows {
+            top -= 1;
+            covered += heights[top]
+        }
 
-        image.clone()
+        let visible = buffer
+            .iter()
+            .enumerate()
+            .skip(top)
+            .map(|(nth, line)| Line {
+                id: nth,
+                spans: match highlight.as_ref() {
+                    Some(pattern) => Self::mark(line, pattern),
+                    None => line.to_vec(),
+                },
+                selected: self.selection.columns(nth + buffer.dropped()),
+                stamp: buffer
+                    .time(nth)
+                    .map(|time| DateTime::<Local>::from(time).format("%H:%M:%S").to_string()),
+            })
+            .collect::<Vec<_>>();
+
+        let cursor = column
+            .filter(|_| offset == 0)
+            .map(|column| (input - top, column));
+
+        Terminal::new(visible, self.rows)
+            .scroll(offset)
+            .gutter(if self.timestamps { Self::GUTTER } else { 0 })
+            .cursor(cursor, self.cursor)
+            .on_select(on_select)
+            .on_link(on_link)
+            .on_resize(on_resize)
+            .into()
     }
 }
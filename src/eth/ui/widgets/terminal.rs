@@ -0,0 +1,630 @@
+// SPDX-FileCopyrightText: Copyright 2023 James M. Putnam (putnamjm.design@gmail.com)
+// SPDX-License-Identifier: MIT
+#![allow(unused_imports)]
+#![allow(dead_code)]
+
+// monospace character grid
+//
+use {
+    iced_native::{
+        alignment, event,
+        layout::{self, Layout},
+        mouse, renderer, text,
+        widget::{self, tree, Tree, Widget},
+        window, Clipboard, Color, Element, Event, Font, Length, Point, Rectangle, Shell, Size,
+    },
+    std::{
+        cell::{Ref, RefCell},
+        time::{Duration, Instant},
+    },
+};
+
+// a run of same-styled text, links are clickable
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub color: Option<Color>,
+    pub background: Option<Color>,
+    pub underline: bool,
+    pub link: Option<usize>,
+}
+
+impl Span {
+    pub fn new(text: &str, color: Option<Color>) -> Self {
+        Span {
+            text: text.to_string(),
+            color,
+            ..Default::default()
+        }
+    }
+
+    pub fn plain(text: &str) -> Self {
+        Self::new(text, None)
+    }
+
+    pub fn link(text: &str, link: usize) -> Self {
+        Span {
+            text: text.to_string(),
+            color: Some(Color::from_rgb8(0x1a, 0x4e, 0xa8)),
+            link: Some(link),
+            ..Default::default()
+        }
+    }
+
+    // everything but the text
+    pub fn same_style(&self, other: &Span) -> bool {
+        self.color == other.color
+            && self.background == other.background
+            && self.underline == other.underline
+            && self.link == other.link
+    }
+
+    pub fn with_text(&self, text: &str) -> Self {
+        Span {
+            text: text.to_string(),
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Cursor {
+    #[default]
+    Block,
+    Underline,
+}
+
+// a logical line, wrapped to the grid width when drawn. selected is a
+// range of columns, usize::MAX runs the selection to the right edge, and
+// stamp is drawn in the gutter next to the line's first row
+#[derive(Debug, Clone, Default)]
+pub struct Line {
+    pub id: usize,
+    pub spans: Vec<Span>,
+    pub selected: Option<(usize, usize)>,
+    pub stamp: Option<String>,
+}
+
+// a display row, the cells of one wrapped piece of a line
+struct Row {
+    // index into lines
+    line: usize,
+    // each char with the index of its span in the line
+    cells: Vec<(char, usize)>,
+    // column of the first cell in the line
+    start: usize,
+}
+
+struct Metrics {
+    cell: Size,
+    columns: usize,
+    // where the first column starts, right of the gutter
+    left: f32,
+}
+
+struct State {
+    epoch: Instant,
+    now: Instant,
+    // line id and column under the mouse while the left button is down
+    drag: Option<(usize, usize)>,
+    // the width last reported to on_resize
+    columns: usize,
+}
+
+impl State {
+    const BLINK: Duration = Duration::from_millis(500);
+
+    fn new() -> Self {
+        let now = Instant::now();
+
+        State {
+            epoch: now,
+            now,
+            drag: None,
+            columns: 0,
+        }
+    }
+
+    fn is_visible(&self) -> bool {
+        (self.now.duration_since(self.epoch).as_millis() / Self::BLINK.as_millis()) % 2 == 0
+    }
+
+    fn next_blink(&self) -> Instant {
+        let elapsed = self.now.duration_since(self.epoch).as_millis();
+        let period = Self::BLINK.as_millis();
+
+        self.epoch + Duration::from_millis(((elapsed / period + 1) * period) as u64)
+    }
+}
+
+pub struct Terminal<Message> {
+    lines: Vec<Line>,
+    rows: usize,
+    // wrapped rows hidden below the bottom of the grid
+    scroll: usize,
+    // columns at the left for the line stamps
+    gutter: usize,
+    // index into lines and column
+    cursor: Option<(usize, usize)>,
+    cursor_style: Cursor,
+    blink: bool,
+    size: Option<f32>,
+    // line id, column, and whether the mouse is dragging
+    on_select: Option<fn(usize, usize, bool) -> Message>,
+    on_link: Option<fn(usize) -> Message>,
+    on_resize: Option<fn(usize) -> Message>,
+    // rows wrapped to a column count, redraws and mouse moves reuse them
+    wrapped: RefCell<Option<(usize, Vec<Row>)>>,
+}
+
+impl<Message> Terminal<Message> {
+    // the grid is measured and drawn in this font
+    const FONT: Font = Font::External {
+        name: "DejaVu Sans Mono",
+        bytes: include_bytes!("../../fonts/DejaVuSansMono.ttf"),
+    };
+
+    const SELECTION: Color = Color {
+        r: 0.78,
+        g: 0.85,
+        b: 0.95,
+        a: 1.0,
+    };
+
+    const STAMP: Color = Color {
+        r: 0.5,
+        g: 0.5,
+        b: 0.5,
+        a: 1.0,
+    };
+
+    pub fn new(lines: Vec<Line>, rows: usize) -> Self {
+        Terminal {
+            lines,
+            rows,
+            scroll: 0,
+            gutter: 0,
+            cursor: None,
+            cursor_style: Cursor::Block,
+            blink: true,
+            size: None,
+            on_select: None,
+            on_link: None,
+            on_resize: None,
+            wrapped: RefCell::new(None),
+        }
+    }
+
+    pub fn scroll(self, scroll: usize) -> Self {
+        Terminal { scroll, ..self }
+    }
+
+    pub fn gutter(self, gutter: usize) -> Self {
+        Terminal { gutter, ..self }
+    }
+
+    pub fn cursor(self, cursor: Option<(usize, usize)>, style: Cursor) -> Self {
+        Terminal {
+            cursor,
+            cursor_style: style,
+            ..self
+        }
+    }
+
+    pub fn blink(self, blink: bool) -> Self {
+        Terminal { blink, ..self }
+    }
+
+    pub fn size(self, size: f32) -> Self {
+        Terminal {
+            size: Some(size),
+            ..self
+        }
+    }
+
+    pub fn on_select(self, on_select: fn(usize, usize, bool) -> Message) -> Self {
+        Terminal {
+            on_select: Some(on_select),
+            ..self
+        }
+    }
+
+    pub fn on_link(self, on_link: fn(usize) -> Message) -> Self {
+        Terminal {
+            on_link: Some(on_link),
+            ..self
+        }
+    }
+
+    pub fn on_resize(self, on_resize: fn(usize) -> Message) -> Self {
+        Terminal {
+            on_resize: Some(on_resize),
+            ..self
+        }
+    }
+
+    fn text_size<Renderer: text::Renderer>(&self, renderer: &Renderer) -> f32 {
+        self.size.unwrap_or_else(|| renderer.default_size())
+    }
+
+    fn metrics<Renderer>(&self, renderer: &Renderer, bounds: Rectangle) -> Metrics
+    where
+        Renderer: text::Renderer<Font = Font>,
+    {
+        let (width, height) =
+            renderer.measure("M", self.text_size(renderer), Self::FONT, Size::INFINITY);
+
+        Metrics {
+            cell: Size::new(width, height),
+            columns: ((bounds.width / width) as usize)
+                .saturating_sub(self.gutter)
+                .max(1),
+            left: bounds.x + self.gutter as f32 * width,
+        }
+    }
+
+    // the wrapped rows on the grid, rewrapped when the columns change
+    fn wrap(&self, columns: usize) -> Ref<'_, Vec<Row>> {
+        let stale = match &*self.wrapped.borrow() {
+            Some((wrapped, _)) => *wrapped != columns,
+            None => true,
+        };
+
+        if stale {
+            *self.wrapped.borrow_mut() = Some((columns, self.wrap_lines(columns)))
+        }
+
+        Ref::map(self.wrapped.borrow(), |wrapped| {
+            &wrapped.as_ref().unwrap().1
+        })
+    }
+
+    fn wrap_lines(&self, columns: usize) -> Vec<Row> {
+        let mut rows = Vec::new();
+
+        for (nth, line) in self.lines.iter().enumerate() {
+            let cells = line
+                .spans
+                .iter()
+                .enumerate()
+                .flat_map(|(index, span)| span.text.chars().map(move |ch| (ch, index)))
+                .collect::<Vec<_>>();
+
+            // the cursor line keeps a row for a cursor past the end
+            let width = match self.cursor {
+                Some((at, column)) if at == nth => cells.len().max(column + 1),
+                _ => cells.len(),
+            };
+
+            for start in (0..width.max(1)).step_by(columns) {
+                rows.push(Row {
+                    line: nth,
+                    cells: cells.iter().skip(start).take(columns).cloned().collect(),
+                    start,
+                })
+            }
+        }
+
+        // scrolled up from the bottom
+        let end = rows.len().saturating_sub(self.scroll);
+
+        rows.truncate(end);
+        rows.split_off(end.saturating_sub(self.rows))
+    }
+
+    // the line, the column in it and the span under point
+    fn hit(
+        &self,
+        rows: &[Row],
+        metrics: &Metrics,
+        bounds: Rectangle,
+        point: Point,
+    ) -> Option<(&Line, usize, Option<&Span>)> {
+        if !bounds.contains(point) {
+            return None;
+        }
+
+        let row = rows.get(((point.y - bounds.y) / metrics.cell.height) as usize)?;
+        let column = ((point.x - metrics.left).max(0.0) / metrics.cell.width) as usize;
+        let line = &self.lines[row.line];
+
+        Some((
+            line,
+            row.start + column,
+            row.cells.get(column).map(|(_, span)| &line.spans[*span]),
+        ))
+    }
+}
+
+impl<Message, Renderer> Widget<Message, Renderer> for Terminal<Message>
+where
+    Message: Clone,
+    Renderer: text::Renderer<Font = Font>,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::new())
+    }
+
+    fn width(&self) -> Length {
+        Length::Fill
+    }
+
+    fn height(&self) -> Length {
+        Length::Shrink
+    }
+
+    // rows high, or as high as the wrapped lines when there are fewer
+    fn layout(&self, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        let limits = limits.width(Length::Fill).height(Length::Shrink);
+        let metrics = self.metrics(
+            renderer,
+            Rectangle::new(Point::ORIGIN, Size::new(limits.max().width, 0.0)),
+        );
+        let rows = self.wrap(metrics.columns).len();
+
+        let size = limits.resolve(Size::new(0.0, metrics.cell.height * rows as f32));
+
+        layout::Node::new(size)
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        let state = tree.state.downcast_mut::<State>();
+
+        match event {
+            Event::Window(window::Event::RedrawRequested(now)) => {
+                state.now = now;
+                if let Some(on_resize) = self.on_resize {
+                    let metrics = self.metrics(renderer, layout.bounds());
+
+                    if metrics.columns != state.columns {
+                        state.columns = metrics.columns;
+                        shell.publish(on_resize(metrics.columns))
+                    }
+                }
+                if self.blink && self.cursor.is_some() {
+                    shell.request_redraw(window::RedrawRequest::At(state.next_blink()))
+                }
+
+                event::Status::Ignored
+            }
+            // typing shows the cursor straight away
+            Event::Keyboard(_) => {
+                state.epoch = Instant::now();
+                event::Status::Ignored
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let bounds = layout.bounds();
+                let metrics = self.metrics(renderer, bounds);
+                let rows = self.wrap(metrics.columns);
+
+                match self.hit(&rows, &metrics, bounds, cursor_position) {
+                    Some((line, column, span)) => {
+                        match (
+                            span.and_then(|span| span.link),
+                            self.on_link,
+                            self.on_select,
+                        ) {
+                            (Some(link), Some(on_link), _) => shell.publish(on_link(link)),
+                            (_, _, Some(on_select)) => {
+                                state.drag = Some((line.id, column));
+                                shell.publish(on_select(line.id, column, false))
+                            }
+                            _ => return event::Status::Ignored,
+                        }
+
+                        event::Status::Captured
+                    }
+                    None => event::Status::Ignored,
+                }
+            }
+            // dragging past the edge selects up to the nearest cell
+            Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                let (drag, on_select) = match (state.drag, self.on_select) {
+                    (Some(drag), Some(on_select)) => (drag, on_select),
+                    _ => return event::Status::Ignored,
+                };
+                let bounds = layout.bounds();
+                let metrics = self.metrics(renderer, bounds);
+                let rows = self.wrap(metrics.columns);
+                let position = Point::new(
+                    position.x.clamp(bounds.x, bounds.x + bounds.width - 1.0),
+                    position.y.clamp(bounds.y, bounds.y + bounds.height - 1.0),
+                );
+
+                match self.hit(&rows, &metrics, bounds, position) {
+                    Some((line, column, _)) if (line.id, column) != drag => {
+                        state.drag = Some((line.id, column));
+                        shell.publish(on_select(line.id, column, true));
+                        event::Status::Captured
+                    }
+                    _ => event::Status::Ignored,
+                }
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                state.drag = None;
+                event::Status::Ignored
+            }
+            _ => event::Status::Ignored,
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        _tree: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        _viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let bounds = layout.bounds();
+        let metrics = self.metrics(renderer, bounds);
+        let rows = self.wrap(metrics.columns);
+
+        match self.hit(&rows, &metrics, bounds, cursor_position) {
+            Some((_, _, Some(span))) if span.link.is_some() => mouse::Interaction::Pointer,
+            Some(_) => mouse::Interaction::Text,
+            None => mouse::Interaction::Idle,
+        }
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        _theme: &Renderer::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        _cursor_position: Point,
+        _viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_ref::<State>();
+        let bounds = layout.bounds();
+        let metrics = self.metrics(renderer, bounds);
+        let size = self.text_size(renderer);
+        let cell = metrics.cell;
+
+        let cursor = match self.cursor {
+            Some(_) if self.blink && !state.is_visible() => None,
+            cursor => cursor,
+        };
+
+        let quad = |x: f32, y: f32, width: f32, height: f32| renderer::Quad {
+            bounds: Rectangle {
+                x,
+                y,
+                width,
+                height,
+            },
+            border_radius: 0.0.into(),
+            border_width: 0.0,
+            border_color: Color::TRANSPARENT,
+        };
+
+        for (nth, row) in self.wrap(metrics.columns).iter().enumerate() {
+            let y = bounds.y + nth as f32 * cell.height;
+            let line = &self.lines[row.line];
+
+            // the part of the selection on this row
+            if let Some((start, end)) = line.selected {
+                let first = start.max(row.start) - row.start;
+                let last = match end {
+                    usize::MAX => metrics.columns,
+                    _ => end.saturating_sub(row.start).min(metrics.columns),
+                };
+
+                if first < last {
+                    let x = metrics.left + first as f32 * cell.width;
+                    let width = match end {
+                        usize::MAX => bounds.x + bounds.width - x,
+                        _ => (last - first) as f32 * cell.width,
+                    };
+
+                    renderer.fill_quad(quad(x, y, width, cell.height), Self::SELECTION)
+                }
+            }
+
+            let cursor_column = match cursor {
+                Some((at, column))
+                    if at == row.line
+                        && column >= row.start
+                        && column < row.start + metrics.columns =>
+                {
+                    Some(column - row.start)
+                }
+                _ => None,
+            };
+
+            if let Some(stamp) = line.stamp.as_ref().filter(|_| row.start == 0) {
+                renderer.fill_text(text::Text {
+                    content: stamp,
+                    bounds: Rectangle {
+                        x: bounds.x,
+                        y,
+                        width: metrics.left - bounds.x,
+                        height: cell.height,
+                    },
+                    size,
+                    color: Self::STAMP,
+                    font: Self::FONT,
+                    horizontal_alignment: alignment::Horizontal::Left,
+                    vertical_alignment: alignment::Vertical::Top,
+                })
+            }
+
+            for (column, (ch, span)) in row.cells.iter().enumerate() {
+                let span = &line.spans[*span];
+                let x = metrics.left + column as f32 * cell.width;
+                let mut color = span.color.unwrap_or(style.text_color);
+
+                if let Some(background) = span.background {
+                    renderer.fill_quad(quad(x, y, cell.width, cell.height), background)
+                }
+
+                if cursor_column == Some(column) && self.cursor_style == Cursor::Block {
+                    renderer.fill_quad(quad(x, y, cell.width, cell.height), style.text_color);
+                    color = span.background.unwrap_or(Color::WHITE)
+                }
+
+                if span.underline {
+                    renderer.fill_quad(quad(x, y + cell.height - 1.0, cell.width, 1.0), color)
+                }
+
+                if !ch.is_whitespace() {
+                    renderer.fill_text(text::Text {
+                        content: &ch.to_string(),
+                        bounds: Rectangle {
+                            x: x + cell.width / 2.0,
+                            y,
+                            width: cell.width,
+                            height: cell.height,
+                        },
+                        size,
+                        color,
+                        font: Self::FONT,
+                        horizontal_alignment: alignment::Horizontal::Center,
+                        vertical_alignment: alignment::Vertical::Top,
+                    })
+                }
+            }
+
+            // a block over text was drawn with the text
+            if let Some(column) = cursor_column {
+                let x = metrics.left + column as f32 * cell.width;
+
+                match self.cursor_style {
+                    Cursor::Block if column < row.cells.len() => (),
+                    Cursor::Block => {
+                        renderer.fill_quad(quad(x, y, cell.width, cell.height), style.text_color)
+                    }
+                    Cursor::Underline => renderer.fill_quad(
+                        quad(x, y + cell.height - 2.0, cell.width, 2.0),
+                        style.text_color,
+                    ),
+                }
+            }
+        }
+    }
+}
+
+impl<'a, Message, Renderer> From<Terminal<Message>> for Element<'a, Message, Renderer>
+where
+    Message: Clone + 'a,
+    Renderer: text::Renderer<Font = Font> + 'a,
+{
+    fn from(terminal: Terminal<Message>) -> Self {
+        Self::new(terminal)
+    }
+}
@@ -17,12 +17,18 @@ use {
     },
     iced::{
         alignment::{Horizontal, Vertical},
-        executor, subscription, theme,
+        clipboard, executor,
+        keyboard::{self, KeyCode, Modifiers},
+        mouse, subscription, theme, time,
         widget::{column, container, horizontal_rule, row, text, Column, Container, Row, Text},
         Alignment, Application, Command, Element, Event, Length, Subscription, Theme,
     },
     iced_aw::{TabLabel, Tabs},
     mu::Mu,
+    std::{
+        collections::BTreeMap,
+        time::{Duration, Instant},
+    },
 };
 
 #[derive(Debug, Default)]
@@ -69,14 +75,32 @@ impl StatusBar {
     }
 }
 
+// the tab that gets keyboard input, widgets inside a tab that take
+// focus (text inputs) capture their events before we ever see them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    About,
+    Scratchpad,
+    Listener(usize),
+    Inspector,
+}
+
 // application tab bar
 pub struct Ui {
     active_tab: usize,
+    focus: Focus,
+    modifiers: Modifiers,
+    // iced can't reach the X primary selection, so middle-click pastes
+    // the last text selected in eth, or the clipboard before there is any
+    primary: Option<String>,
     env: Environment,
     poll_interval_secs: u64,
     version: String,
     about_tab: AboutTab,
-    listener_tab: ListenerTab,
+    // listeners by session id, ids aren't reused so messages still in
+    // flight for a closed session find nothing
+    listener_tabs: BTreeMap<usize, ListenerTab>,
+    sessions: usize,
     inspector_tab: InspectorTab,
     scratchpad_tab: ScratchpadTab,
 }
@@ -85,8 +109,17 @@ pub struct Ui {
 pub enum Message {
     TabSelected(usize),
     EventOccurred(Event),
+    Tick(Instant),
+    // mouse selection and value links in the focused tab, selection
+    // reports line, column and whether the mouse is dragging
+    Select(usize, usize, bool),
+    InspectValue(usize),
+    // the focused tab's terminal width in columns
+    Resize(usize),
+    NewSession(bool),
+    CloseSession(usize),
     About(AboutMessage),
-    Listener(ListenerMessage),
+    Listener(usize, ListenerMessage),
     Scratchpad(ScratchpadMessage),
     Inspector(InspectorMessage),
 }
@@ -98,14 +131,20 @@ impl Application for Ui {
     type Message = Message;
 
     fn new<'a>(env: Environment) -> (Ui, Command<Message>) {
+        let listener_tab = ListenerTab::new(&env, "listener", None);
+
         let tab_bar = Ui {
             active_tab: 0,
+            focus: Focus::About,
+            modifiers: Modifiers::default(),
+            primary: None,
             env,
             poll_interval_secs: 10,
             version: "0.0.4".to_string(),
             about_tab: AboutTab::new(),
             inspector_tab: InspectorTab::new(),
-            listener_tab: ListenerTab::new(),
+            listener_tabs: BTreeMap::from([(0, listener_tab)]),
+            sessions: 1,
             scratchpad_tab: ScratchpadTab::new(),
         };
 
@@ -147,50 +186,305 @@ impl Application for Ui {
         format!("eth {}", self.version)
     }
 
+    // the inspector refreshes itself on a timer while it's showing
     fn subscription(&self) -> Subscription<Message> {
-        subscription::events().map(Message::EventOccurred)
+        let events = subscription::events().map(Message::EventOccurred);
+
+        match self.focus {
+            Focus::Inspector => Subscription::batch(vec![
+                events,
+                time::every(Duration::from_secs(1)).map(Message::Tick),
+            ]),
+            _ => events,
+        }
     }
 
     fn update(&mut self, message: Self::Message) -> Command<Message> {
         match message {
-            Message::TabSelected(selected) => {
-                self.active_tab = selected;
-                self.inspector_tab
-                    .update(&self.env, InspectorMessage::Refresh);
-                self.about_tab.update(&self.env, AboutMessage::Refresh)
-            }
+            Message::TabSelected(selected) => self.select_tab(selected),
+            Message::Tick(now) => self
+                .inspector_tab
+                .update(&self.env, InspectorMessage::Tick(now)),
             Message::EventOccurred(event) => {
-                self.listener_tab
-                    .update(&self.env, ListenerMessage::EventOccurred(event));
+                if let Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) = event {
+                    self.modifiers = modifiers
+                }
+
+                match self.shortcut(&event) {
+                    Some(command) => return command,
+                    None => self.route(event),
+                }
             }
-            Message::Listener(message) => self.listener_tab.update(&self.env, message),
+            Message::Select(line, column, drag) => self.select(line, column, drag),
+            Message::InspectValue(nth) => self.inspect_value(nth),
+            Message::Resize(columns) => self.resize(columns),
+            Message::NewSession(fresh) => self.new_session(fresh),
+            Message::CloseSession(id) => self.close_session(id),
+            Message::Listener(id, message) => self.listener_update(id, message),
             Message::Scratchpad(message) => self.scratchpad_tab.update(message),
             Message::About(message) => self.about_tab.update(&self.env, message),
-            Message::Inspector(message) => self.inspector_tab.update(&self.env, message),
+            Message::Inspector(message) => {
+                self.inspector_tab.update(&self.env, message);
+                for message in self.inspector_tab.take_log() {
+                    self.about_tab.log(message)
+                }
+            }
         }
 
         Command::none()
     }
 
     fn view(&self) -> Element<'_, Self::Message> {
-        Tabs::new(self.active_tab, Message::TabSelected)
+        let mut tabs = Tabs::new(self.active_tab, Message::TabSelected)
             .push(self.about_tab.tab_label(), self.about_tab.view(&self.env))
             .push(
                 self.scratchpad_tab.tab_label(),
                 self.scratchpad_tab.view(&self.env),
-            )
-            .push(
-                self.listener_tab.tab_label(),
-                self.listener_tab.view(&self.env),
-            )
-            .push(
-                self.inspector_tab.tab_label(),
-                self.inspector_tab.view(&self.env),
-            )
+            );
+
+        for (id, listener_tab) in &self.listener_tabs {
+            tabs = tabs.push(listener_tab.tab_label(), listener_tab.view(&self.env, *id))
+        }
+
+        tabs.push(self.inspector_tab.tab_label(), self.inspector_tab.view())
             .into()
     }
 }
 
+impl Ui {
+    // about, scratchpad, the listeners, then the inspector
+    fn tabs(&self) -> usize {
+        self.listener_tabs.len() + 3
+    }
+
+    fn from_tab(&self, tab: usize) -> Focus {
+        match tab {
+            0 => Focus::About,
+            1 => Focus::Scratchpad,
+            _ => match self.listener_tabs.keys().nth(tab - 2) {
+                Some(id) => Focus::Listener(*id),
+                None => Focus::Inspector,
+            },
+        }
+    }
+
+    fn to_tab(&self, focus: Focus) -> usize {
+        match focus {
+            Focus::About => 0,
+            Focus::Scratchpad => 1,
+            Focus::Listener(id) => {
+                self.listener_tabs
+                    .keys()
+                    .position(|key| *key == id)
+                    .unwrap_or(0)
+                    + 2
+            }
+            Focus::Inspector => self.listener_tabs.len() + 2,
+        }
+    }
+
+    // a fresh session gets its own core built from the same config
+    fn new_session(&mut self, fresh: bool) {
+        let id = self.sessions;
+
+        self.sessions += 1;
+
+        let core = if fresh {
+            Some(Core::new(&self.env.config, &self.env.config_path))
+        } else {
+            None
+        };
+        let name = format!("session {}{}", id + 1, if fresh { " (fresh)" } else { "" });
+
+        self.about_tab.log(format!("listener: opened {}", name));
+        self.listener_tabs
+            .insert(id, ListenerTab::new(&self.env, &name, core));
+        self.select_tab(self.to_tab(Focus::Listener(id)))
+    }
+
+    // the first listener is always there, focus goes to the tab before
+    fn close_session(&mut self, id: usize) {
+        let tab = self.to_tab(Focus::Listener(id));
+
+        if id == 0 || self.listener_tabs.remove(&id).is_none() {
+            return;
+        }
+
+        self.select_tab(tab - 1)
+    }
+
+    // evaluations in the main core can change what the inspector shows
+    fn listener_update(&mut self, id: usize, message: ListenerMessage) {
+        let listener = match self.listener_tabs.get_mut(&id) {
+            Some(listener) => listener,
+            None => return,
+        };
+        let evaluations = listener.evaluations();
+
+        listener.update(&self.env, message);
+        for message in listener.take_log() {
+            self.about_tab.log(message)
+        }
+
+        if listener.is_shared() && listener.evaluations() != evaluations {
+            self.inspector_tab
+                .update(&self.env, InspectorMessage::Refresh)
+        }
+    }
+
+    fn select_tab(&mut self, tab: usize) {
+        self.active_tab = tab;
+        self.focus = self.from_tab(tab);
+
+        // only the tab we're switching to needs fresh state
+        match self.focus {
+            Focus::Inspector => self
+                .inspector_tab
+                .update(&self.env, InspectorMessage::Refresh),
+            Focus::About => self.about_tab.update(&self.env, AboutMessage::Refresh),
+            Focus::Listener(_) | Focus::Scratchpad => (),
+        }
+    }
+
+    // a click selects lines and shift extends the selection, dragging
+    // selects the text from the click
+    fn select(&mut self, line: usize, column: usize, drag: bool) {
+        let extend = self.modifiers.shift();
+
+        self.primary = match self.focus {
+            Focus::Listener(id) => match self.listener_tabs.get(&id) {
+                Some(listener) => {
+                    match drag {
+                        true => listener.drag(line, column),
+                        false => listener.select(line, column, extend),
+                    }
+                    listener.selection()
+                }
+                None => return,
+            },
+            Focus::About => {
+                match drag {
+                    true => self.about_tab.drag(line, column),
+                    false => self.about_tab.select(line, column, extend),
+                }
+                self.about_tab.selection()
+            }
+            Focus::Scratchpad | Focus::Inspector => return,
+        }
+    }
+
+    fn resize(&mut self, columns: usize) {
+        if let Focus::Listener(id) = self.focus {
+            if let Some(listener) = self.listener_tabs.get(&id) {
+                listener.resize(columns)
+            }
+        }
+    }
+
+    fn inspect_value(&mut self, nth: usize) {
+        let listener = match self.focus {
+            Focus::Listener(id) => match self.listener_tabs.get(&id) {
+                Some(listener) => listener,
+                None => return,
+            },
+            _ => return,
+        };
+
+        // the inspector only knows the main core
+        if !listener.is_shared() {
+            self.about_tab
+                .log("inspector: session has its own core, can't inspect".to_string())
+        } else if let Some(repr) = listener.value(nth) {
+            self.inspector_tab
+                .update(&self.env, InspectorMessage::Inspect(repr));
+            self.select_tab(self.to_tab(Focus::Inspector))
+        }
+    }
+
+    fn copy(&self) -> Command<Message> {
+        let selection = match self.focus {
+            Focus::Listener(id) => self
+                .listener_tabs
+                .get(&id)
+                .and_then(|listener| listener.selection()),
+            Focus::About => self.about_tab.selection(),
+            Focus::Scratchpad | Focus::Inspector => None,
+        };
+
+        match selection {
+            Some(text) => clipboard::write(text),
+            None => Command::none(),
+        }
+    }
+
+    fn paste(&self) -> Command<Message> {
+        match self.focus {
+            Focus::Listener(id) => {
+                clipboard::read(move |text| Message::Listener(id, ListenerMessage::Paste(text)))
+            }
+            Focus::About | Focus::Scratchpad | Focus::Inspector => Command::none(),
+        }
+    }
+
+    fn paste_primary(&mut self) -> Command<Message> {
+        match (self.focus, self.primary.clone()) {
+            (Focus::Listener(id), Some(text)) => {
+                self.listener_update(id, ListenerMessage::Paste(Some(text)));
+                Command::none()
+            }
+            _ => self.paste(),
+        }
+    }
+
+    // global shortcuts are handled before per-tab routing
+    fn shortcut(&mut self, event: &Event) -> Option<Command<Message>> {
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Middle)) => {
+                Some(self.paste_primary())
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key_code,
+                modifiers,
+            }) if modifiers.control() => {
+                let tab = match key_code {
+                    KeyCode::C if modifiers.shift() => return Some(self.copy()),
+                    KeyCode::V => return Some(self.paste()),
+                    KeyCode::Key1 => self.to_tab(Focus::About),
+                    KeyCode::Key2 => self.to_tab(Focus::Scratchpad),
+                    KeyCode::Key3 => self.to_tab(Focus::Listener(0)),
+                    KeyCode::Key4 => self.to_tab(Focus::Inspector),
+                    KeyCode::PageUp => (self.active_tab + self.tabs() - 1) % self.tabs(),
+                    KeyCode::PageDown => (self.active_tab + 1) % self.tabs(),
+                    _ => return None,
+                };
+
+                self.select_tab(tab);
+                Some(Command::none())
+            }
+            _ => None,
+        }
+    }
+
+    // keyboard and mouse events go to the focused tab only
+    fn route(&mut self, event: Event) {
+        match event {
+            // printable characters typed with control held are shortcut leftovers
+            Event::Keyboard(keyboard::Event::CharacterReceived(ch))
+                if self.modifiers.control() && !ch.is_control() => {}
+            Event::Keyboard(_) | Event::Mouse(_) => match self.focus {
+                Focus::Listener(id) => {
+                    self.listener_update(id, ListenerMessage::EventOccurred(event))
+                }
+                Focus::Inspector => self
+                    .inspector_tab
+                    .update(&self.env, InspectorMessage::EventOccurred(event)),
+                Focus::About | Focus::Scratchpad => (),
+            },
+            _ => (),
+        }
+    }
+}
+
 pub trait Tab {
     const HEADER_SIZE: u16 = 32;
     const TAB_PADDING: u16 = 16;